@@ -0,0 +1,144 @@
+//! A C API for the most common metrics, enabled by the `ffi` feature.
+//!
+//! All functions take null-terminated UTF-8 strings. If an argument is null or
+//! not valid UTF-8, a sentinel value is returned instead of a result.
+//!
+//! Build a C library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`).
+
+#![allow(unsafe_code)]
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+/// Returned by [`strsim_levenshtein`] if an argument is invalid.
+pub const STRSIM_INVALID_DISTANCE: usize = usize::MAX;
+
+/// Returned by [`strsim_jaro`] and [`strsim_jaro_winkler`] if an argument is
+/// invalid.
+pub const STRSIM_INVALID_SIMILARITY: f64 = -1.0;
+
+/// Converts a C string to a `&str`, returning `None` if the pointer is null or
+/// the string is not valid UTF-8.
+///
+/// # Safety
+///
+/// `ptr` has to be null or point to a valid null-terminated string that stays
+/// alive for `'a`.
+unsafe fn to_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// C version of [`levenshtein`](crate::levenshtein). Returns
+/// [`STRSIM_INVALID_DISTANCE`] if an argument is null or not valid UTF-8.
+///
+/// # Safety
+///
+/// Both arguments have to be null or point to valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn strsim_levenshtein(a: *const c_char, b: *const c_char) -> usize {
+    match (to_str(a), to_str(b)) {
+        (Some(a), Some(b)) => ::levenshtein(a, b),
+        _ => STRSIM_INVALID_DISTANCE,
+    }
+}
+
+/// C version of [`jaro`](crate::jaro). Returns [`STRSIM_INVALID_SIMILARITY`]
+/// if an argument is null or not valid UTF-8.
+///
+/// # Safety
+///
+/// Both arguments have to be null or point to valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn strsim_jaro(a: *const c_char, b: *const c_char) -> f64 {
+    match (to_str(a), to_str(b)) {
+        (Some(a), Some(b)) => ::jaro(a, b),
+        _ => STRSIM_INVALID_SIMILARITY,
+    }
+}
+
+/// C version of [`jaro_winkler`](crate::jaro_winkler). Returns
+/// [`STRSIM_INVALID_SIMILARITY`] if an argument is null or not valid UTF-8.
+///
+/// # Safety
+///
+/// Both arguments have to be null or point to valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn strsim_jaro_winkler(a: *const c_char, b: *const c_char) -> f64 {
+    match (to_str(a), to_str(b)) {
+        (Some(a), Some(b)) => ::jaro_winkler(a, b),
+        _ => STRSIM_INVALID_SIMILARITY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn ffi_levenshtein() {
+        let a = CString::new("kitten").unwrap();
+        let b = CString::new("sitting").unwrap();
+        assert_eq!(3, unsafe { strsim_levenshtein(a.as_ptr(), b.as_ptr()) });
+    }
+
+    #[test]
+    fn ffi_jaro() {
+        let a = CString::new("dixon").unwrap();
+        let b = CString::new("dicksonx").unwrap();
+        assert_eq!(::jaro("dixon", "dicksonx"), unsafe {
+            strsim_jaro(a.as_ptr(), b.as_ptr())
+        });
+        assert_eq!(::jaro_winkler("dixon", "dicksonx"), unsafe {
+            strsim_jaro_winkler(a.as_ptr(), b.as_ptr())
+        });
+    }
+
+    #[test]
+    fn ffi_multibyte() {
+        let a = CString::new("öঙ香").unwrap();
+        let b = CString::new("abc").unwrap();
+        assert_eq!(3, unsafe { strsim_levenshtein(a.as_ptr(), b.as_ptr()) });
+    }
+
+    #[test]
+    fn ffi_null() {
+        let a = CString::new("kitten").unwrap();
+        unsafe {
+            assert_eq!(
+                STRSIM_INVALID_DISTANCE,
+                strsim_levenshtein(a.as_ptr(), ptr::null())
+            );
+            assert_eq!(
+                STRSIM_INVALID_SIMILARITY,
+                strsim_jaro(ptr::null(), a.as_ptr())
+            );
+            assert_eq!(
+                STRSIM_INVALID_SIMILARITY,
+                strsim_jaro_winkler(ptr::null(), ptr::null())
+            );
+        }
+    }
+
+    #[test]
+    fn ffi_invalid_utf8() {
+        let a = CString::new(vec![0x66, 0x6f, 0xff]).unwrap();
+        let b = CString::new("foo").unwrap();
+        unsafe {
+            assert_eq!(
+                STRSIM_INVALID_DISTANCE,
+                strsim_levenshtein(a.as_ptr(), b.as_ptr())
+            );
+            assert_eq!(
+                STRSIM_INVALID_SIMILARITY,
+                strsim_jaro_winkler(b.as_ptr(), a.as_ptr())
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! This library implements string similarity metrics.
 
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "ffi", deny(unsafe_code))]
 #![allow(
     // these casts are sometimes needed. They restrict the length of input iterators
     // but there isn't really any way around this except for always working with
@@ -19,28 +20,87 @@
     clippy::range_plus_one
 )]
 
+#[cfg(feature = "caseless")]
+extern crate caseless;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "unicode")]
+extern crate unicode_normalization;
+#[cfg(feature = "unicode")]
+extern crate unicode_segmentation;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod normalize;
+
+use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::char;
 use std::cmp::{max, min};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::error::Error;
+use std::ffi::OsStr;
 use std::fmt::{self, Display, Formatter};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::mem;
 use std::str::Chars;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "regex")]
+use regex::{NoExpand, Regex};
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
 #[derive(Debug, PartialEq)]
 pub enum StrSimError {
-    DifferentLengthArgs,
+    /// The arguments have a different number of elements (characters for
+    /// strings).
+    DifferentLengthArgs { len_a: usize, len_b: usize },
+    /// An `n`-gram size of 0 was provided.
+    InvalidNgramSize,
+    /// A number of hash functions of 0 was provided.
+    InvalidHashCount,
+    /// An input has `len` characters, more than the allowed `max_len`.
+    InputTooLong { len: usize, max_len: usize },
+    /// `actual` weights were provided where `expected` were needed, one per
+    /// element.
+    WeightCountMismatch { expected: usize, actual: usize },
+    /// `requested` elements were requested, but only `available` exist.
+    OutOfRange { requested: usize, available: usize },
 }
 
 impl Display for StrSimError {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
-        let text = match self {
-            StrSimError::DifferentLengthArgs => "Differing length arguments provided",
-        };
-
-        write!(fmt, "{text}")
+        match self {
+            StrSimError::DifferentLengthArgs { len_a, len_b } => write!(
+                fmt,
+                "Differing length arguments provided: {len_a} and {len_b}"
+            ),
+            StrSimError::InvalidNgramSize => write!(fmt, "n-gram size must be at least 1"),
+            StrSimError::InvalidHashCount => {
+                write!(fmt, "number of hash functions must be at least 1")
+            }
+            StrSimError::InputTooLong { len, max_len } => write!(
+                fmt,
+                "Input of {len} characters exceeds the maximum of {max_len}"
+            ),
+            StrSimError::WeightCountMismatch { expected, actual } => {
+                write!(fmt, "Expected {expected} weights, got {actual}")
+            }
+            StrSimError::OutOfRange {
+                requested,
+                available,
+            } => write!(
+                fmt,
+                "Requested {requested} elements, but only {available} are available"
+            ),
+        }
     }
 }
 
@@ -48,6 +108,43 @@ impl Error for StrSimError {}
 
 pub type HammingResult = Result<usize, StrSimError>;
 
+/// Returns an error if `n` is not a valid `n`-gram size.
+fn check_ngram_size(n: usize) -> Result<(), StrSimError> {
+    if n == 0 {
+        Err(StrSimError::InvalidNgramSize)
+    } else {
+        Ok(())
+    }
+}
+
+/// Calls `f` with the index and the elements at every position of the two
+/// sequences. Returns an error with the lengths of both sequences if they
+/// have different lengths.
+fn zip_equal_length<Iter1, Iter2, F>(a: Iter1, b: Iter2, mut f: F) -> Result<(), StrSimError>
+where
+    Iter1: IntoIterator,
+    Iter2: IntoIterator,
+    F: FnMut(usize, Iter1::Item, Iter2::Item),
+{
+    let (mut ita, mut itb) = (a.into_iter(), b.into_iter());
+    let mut len = 0;
+    loop {
+        match (ita.next(), itb.next()) {
+            (Some(x), Some(y)) => {
+                f(len, x, y);
+                len += 1;
+            }
+            (None, None) => return Ok(()),
+            (x, y) => {
+                return Err(StrSimError::DifferentLengthArgs {
+                    len_a: len + usize::from(x.is_some()) + ita.count(),
+                    len_b: len + usize::from(y.is_some()) + itb.count(),
+                })
+            }
+        }
+    }
+}
+
 /// Calculates the number of positions in the two sequences where the elements
 /// differ. Returns an error if the sequences have different lengths.
 pub fn generic_hamming<Iter1, Iter2, Elem1, Elem2>(a: Iter1, b: Iter2) -> HammingResult
@@ -56,19 +153,13 @@ where
     Iter2: IntoIterator<Item = Elem2>,
     Elem1: PartialEq<Elem2>,
 {
-    let (mut ita, mut itb) = (a.into_iter(), b.into_iter());
     let mut count = 0;
-    loop {
-        match (ita.next(), itb.next()) {
-            (Some(x), Some(y)) => {
-                if x != y {
-                    count += 1;
-                }
-            }
-            (None, None) => return Ok(count),
-            _ => return Err(StrSimError::DifferentLengthArgs),
+    zip_equal_length(a, b, |_, x, y| {
+        if x != y {
+            count += 1;
         }
-    }
+    })?;
+    Ok(count)
 }
 
 /// Calculates the number of positions in the two strings where the characters
@@ -79,15 +170,149 @@ where
 ///
 /// assert_eq!(Ok(3), hamming("hamming", "hammers"));
 ///
-/// assert_eq!(Err(DifferentLengthArgs), hamming("hamming", "ham"));
+/// assert_eq!(
+///     Err(DifferentLengthArgs { len_a: 7, len_b: 3 }),
+///     hamming("hamming", "ham")
+/// );
 /// ```
 pub fn hamming(a: &str, b: &str) -> HammingResult {
     generic_hamming(a.chars(), b.chars())
 }
 
+/// Calculates the Hamming distance together with the normalized similarity
+/// `1 - distance / len` in a single pass, where `len` is the number of
+/// characters of either string. Two empty strings have a similarity of 1.0.
+/// Returns an error if the strings have different lengths.
+///
+/// ```
+/// use strsim::hamming_full;
+///
+/// assert_eq!(Ok((3, 4.0 / 7.0)), hamming_full("karolin", "kathrin"));
+/// assert!(hamming_full("karolin", "kath").is_err());
+/// ```
+pub fn hamming_full(a: &str, b: &str) -> Result<(usize, f64), StrSimError> {
+    let (mut distance, mut len) = (0, 0);
+    zip_equal_length(a.chars(), b.chars(), |_, x, y| {
+        if x != y {
+            distance += 1;
+        }
+        len += 1;
+    })?;
+
+    if len == 0 {
+        return Ok((0, 1.0));
+    }
+    Ok((distance, 1.0 - distance as f64 / len as f64))
+}
+
+/// Like Hamming, but every differing position `i` contributes `weights[i]`
+/// instead of 1. Returns an error if the strings have different lengths, or
+/// [`StrSimError::WeightCountMismatch`] if `weights` doesn't contain exactly
+/// one weight per character.
+///
+/// ```
+/// use strsim::weighted_hamming;
+///
+/// let weights = [1.0, 1.0, 5.0];
+/// assert_eq!(Ok(5.0), weighted_hamming("abc", "abd", &weights));
+/// assert_eq!(Ok(7.0), weighted_hamming("abc", "xyz", &weights));
+/// ```
+pub fn weighted_hamming(a: &str, b: &str, weights: &[f64]) -> Result<f64, StrSimError> {
+    let positions = hamming_positions(a, b)?;
+
+    let len = a.chars().count();
+    if weights.len() != len {
+        return Err(StrSimError::WeightCountMismatch {
+            expected: len,
+            actual: weights.len(),
+        });
+    }
+
+    Ok(positions.into_iter().map(|i| weights[i]).sum())
+}
+
+/// Returns the character indices at which the two strings differ. The number
+/// of indices equals the Hamming distance. Returns an error if the strings
+/// have different lengths.
+///
+/// ```
+/// use strsim::hamming_positions;
+///
+/// assert_eq!(Ok(vec![2, 3, 4]), hamming_positions("karolin", "kathrin"));
+/// ```
+pub fn hamming_positions(a: &str, b: &str) -> Result<Vec<usize>, StrSimError> {
+    let mut positions = Vec::new();
+    zip_equal_length(a.chars(), b.chars(), |i, x, y| {
+        if x != y {
+            positions.push(i);
+        }
+    })?;
+    Ok(positions)
+}
+
+/// Calculates the number of positions in the two byte arrays where the bytes
+/// differ. Since both arrays have the same length by type, this can't fail.
+///
+/// ```
+/// use strsim::hamming_array;
+///
+/// assert_eq!(2, hamming_array(&[1, 2, 3, 4], &[1, 0, 3, 0]));
+/// ```
+pub fn hamming_array<const N: usize>(a: &[u8; N], b: &[u8; N]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// Like Hamming, but characters are compared case-insensitively. Returns an
+/// error if the strings have a different number of characters.
+///
+/// Each pair of characters is compared by their full lowercase mapping, so
+/// characters which lowercase to several characters (e.g. `'İ'`) still only
+/// occupy a single position and the lengths of the original strings are
+/// what counts.
+///
+/// ```
+/// use strsim::hamming_ignore_case;
+///
+/// assert_eq!(Ok(0), hamming_ignore_case("ABCdef", "abcDEF"));
+/// assert_eq!(Ok(2), hamming_ignore_case("Hamming", "hAMMERg"));
+/// ```
+pub fn hamming_ignore_case(a: &str, b: &str) -> HammingResult {
+    let mut count = 0;
+    zip_equal_length(a.chars(), b.chars(), |_, x, y| {
+        if x != y && !x.to_lowercase().eq(y.to_lowercase()) {
+            count += 1;
+        }
+    })?;
+    Ok(count)
+}
+
 /// Calculates the Jaro similarity between two sequences. The returned value
 /// is between 0.0 and 1.0 (higher value means more similar).
 pub fn generic_jaro<'a, 'b, Iter1, Iter2, Elem1, Elem2>(a: &'a Iter1, b: &'b Iter2) -> f64
+where
+    &'a Iter1: IntoIterator<Item = Elem1>,
+    &'b Iter2: IntoIterator<Item = Elem2>,
+    Elem1: PartialEq<Elem2>,
+{
+    jaro_impl(a, b, None).0
+}
+
+/// The integer quantities the Jaro similarity is calculated from.
+struct JaroCounts {
+    a_len: usize,
+    b_len: usize,
+    matches: usize,
+    transpositions: usize,
+}
+
+/// Counts the lengths, matching elements and transpositions of two
+/// sequences, searching for matches within `window` positions. If `window`
+/// is `None`, the standard `max(len) / 2 - 1` is used.
+fn jaro_counts<'a, 'b, Iter1, Iter2, Elem1, Elem2>(
+    a: &'a Iter1,
+    b: &'b Iter2,
+    window: Option<usize>,
+) -> JaroCounts
 where
     &'a Iter1: IntoIterator<Item = Elem1>,
     &'b Iter2: IntoIterator<Item = Elem2>,
@@ -96,14 +321,16 @@ where
     let a_len = a.into_iter().count();
     let b_len = b.into_iter().count();
 
-    if a_len == 0 && b_len == 0 {
-        return 1.0;
-    } else if a_len == 0 || b_len == 0 {
-        return 0.0;
+    if a_len == 0 || b_len == 0 {
+        return JaroCounts {
+            a_len,
+            b_len,
+            matches: 0,
+            transpositions: 0,
+        };
     }
 
-    let mut search_range = max(a_len, b_len) / 2;
-    search_range = search_range.saturating_sub(1);
+    let search_range = window.unwrap_or_else(|| (max(a_len, b_len) / 2).saturating_sub(1));
 
     // combine memory allocations to reduce runtime
     let mut flags_memory = vec![false; a_len + b_len];
@@ -113,6 +340,7 @@ where
 
     for (i, a_elem) in a.into_iter().enumerate() {
         // prevent integer wrapping
+        #[allow(clippy::implicit_saturating_sub)]
         let min_bound = if i > search_range {
             i - search_range
         } else {
@@ -153,18 +381,51 @@ where
     }
     transpositions /= 2;
 
-    if matches == 0 {
+    JaroCounts {
+        a_len,
+        b_len,
+        matches,
+        transpositions,
+    }
+}
+
+/// Calculates the Jaro similarity and the number of matching elements,
+/// searching for matches within `window` positions like [`jaro_counts`].
+fn jaro_impl<'a, 'b, Iter1, Iter2, Elem1, Elem2>(
+    a: &'a Iter1,
+    b: &'b Iter2,
+    window: Option<usize>,
+) -> (f64, usize)
+where
+    &'a Iter1: IntoIterator<Item = Elem1>,
+    &'b Iter2: IntoIterator<Item = Elem2>,
+    Elem1: PartialEq<Elem2>,
+{
+    let JaroCounts {
+        a_len,
+        b_len,
+        matches,
+        transpositions,
+    } = jaro_counts(a, b, window);
+
+    if a_len == 0 && b_len == 0 {
+        return (1.0, 0);
+    }
+
+    let sim = if matches == 0 {
         0.0
     } else {
         ((matches as f64 / a_len as f64)
             + (matches as f64 / b_len as f64)
             + ((matches - transpositions) as f64 / matches as f64))
             / 3.0
-    }
+    };
+    (sim, matches)
 }
 
 struct StringWrapper<'a>(&'a str);
 
+#[allow(clippy::needless_lifetimes)]
 impl<'a, 'b> IntoIterator for &'a StringWrapper<'b> {
     type Item = char;
     type IntoIter = Chars<'b>;
@@ -177,6 +438,14 @@ impl<'a, 'b> IntoIterator for &'a StringWrapper<'b> {
 /// Calculates the Jaro similarity between two strings. The returned value
 /// is between 0.0 and 1.0 (higher value means more similar).
 ///
+/// All lengths are counted in characters (`char`s), never in bytes. Matches
+/// and transpositions are counted as integers and only combined into the
+/// final floating point value once, so no rounding error accumulates for long
+/// strings.
+/// Whitespace and combining marks are ordinary characters, so a string
+/// consisting only of them is not treated as empty: it is only similar to
+/// strings sharing some of those characters.
+///
 /// ```
 /// use strsim::jaro;
 ///
@@ -187,6 +456,60 @@ pub fn jaro(a: &str, b: &str) -> f64 {
     generic_jaro(&StringWrapper(a), &StringWrapper(b))
 }
 
+/// The precise variant of [`jaro`]. Instead of adding up three rounded
+/// fractions, the three terms are brought to the common denominator
+/// `3 * m * len_a * len_b` (with `m` the number of matches) and combined in
+/// integer arithmetic, so the only rounding happens in the final division.
+/// As long as both integers fit into the 53 bit mantissa of an `f64`, which
+/// holds for strings of up to about 100,000 characters, the result is
+/// therefore the closest `f64` to the exact fraction, e.g. exactly 1.0 for
+/// identical strings, while [`jaro`] can be off in the last digits.
+///
+/// ```
+/// use strsim::jaro_exact;
+///
+/// assert_eq!(1.0, jaro_exact("martha", "martha"));
+/// assert_eq!(17.0 / 18.0, jaro_exact("martha", "marhta"));
+/// assert_eq!(0.55, jaro_exact("dicksonx", "duane"));
+/// ```
+pub fn jaro_exact(a: &str, b: &str) -> f64 {
+    let JaroCounts {
+        a_len,
+        b_len,
+        matches,
+        transpositions,
+    } = jaro_counts(&StringWrapper(a), &StringWrapper(b), None);
+
+    if a_len == 0 && b_len == 0 {
+        return 1.0;
+    } else if matches == 0 {
+        return 0.0;
+    }
+
+    let (a_len, b_len) = (a_len as u128, b_len as u128);
+    let (matches, transpositions) = (matches as u128, transpositions as u128);
+    // m / len_a + m / len_b + (m - t) / m over the common denominator
+    let numerator =
+        matches * matches * (a_len + b_len) + (matches - transpositions) * a_len * b_len;
+    let denominator = 3 * matches * a_len * b_len;
+    numerator as f64 / denominator as f64
+}
+
+/// Like Jaro, but characters are considered matching if they are at most
+/// `window` positions apart, instead of the standard `max(len) / 2 - 1`.
+/// Passing `max(len) / 2 - 1` (saturating at 0) therefore reproduces
+/// [`jaro`]. This is mostly useful to experiment with the matching window.
+///
+/// ```
+/// use strsim::{jaro, jaro_windowed};
+///
+/// assert_eq!(jaro("dixon", "dicksonx"), jaro_windowed("dixon", "dicksonx", 3));
+/// assert_eq!(0.0, jaro_windowed("ab", "ba", 0));
+/// ```
+pub fn jaro_windowed(a: &str, b: &str, window: usize) -> f64 {
+    jaro_impl(&StringWrapper(a), &StringWrapper(b), Some(window)).0
+}
+
 /// Like Jaro but gives a boost to sequences that have a common prefix.
 pub fn generic_jaro_winkler<'a, 'b, Iter1, Iter2, Elem1, Elem2>(a: &'a Iter1, b: &'b Iter2) -> f64
 where
@@ -195,13 +518,28 @@ where
     Elem1: PartialEq<Elem2>,
 {
     let sim = generic_jaro(a, b);
+    winkler_boost(a, b, sim, |a_elem, b_elem| a_elem == b_elem)
+}
 
+/// Applies the Winkler prefix boost to an already computed Jaro similarity.
+/// Elements of the common prefix are compared using `eq`.
+fn winkler_boost<'a, 'b, Iter1, Iter2, Elem1, Elem2, F>(
+    a: &'a Iter1,
+    b: &'b Iter2,
+    sim: f64,
+    eq: F,
+) -> f64
+where
+    &'a Iter1: IntoIterator<Item = Elem1>,
+    &'b Iter2: IntoIterator<Item = Elem2>,
+    F: Fn(&Elem1, &Elem2) -> bool,
+{
     if sim > 0.7 {
         let prefix_length = a
             .into_iter()
             .take(4)
             .zip(b)
-            .take_while(|(a_elem, b_elem)| a_elem == b_elem)
+            .take_while(|(a_elem, b_elem)| eq(a_elem, b_elem))
             .count();
 
         sim + 0.1 * prefix_length as f64 * (1.0 - sim)
@@ -222,19 +560,68 @@ pub fn jaro_winkler(a: &str, b: &str) -> f64 {
     generic_jaro_winkler(&StringWrapper(a), &StringWrapper(b))
 }
 
-/// Calculates the minimum number of insertions, deletions, and substitutions
-/// required to change one sequence into the other.
+/// Calculates both the Jaro and the Jaro-Winkler similarity of two strings,
+/// returned as `(jaro, jaro_winkler)`. The Jaro similarity is only computed
+/// once, which is cheaper than calling [`jaro`] and [`jaro_winkler`]
+/// separately.
 ///
 /// ```
-/// use strsim::generic_levenshtein;
+/// use strsim::jaro_winkler_pair;
 ///
-/// assert_eq!(3, generic_levenshtein(&[1,2,3], &[1,2,3,4,5,6]));
+/// let (jaro, jaro_winkler) = jaro_winkler_pair("dixon", "dicksonx");
+/// assert!((0.767 - jaro).abs() < 0.001);
+/// assert!((0.813 - jaro_winkler).abs() < 0.001);
 /// ```
-pub fn generic_levenshtein<'a, 'b, Iter1, Iter2, Elem1, Elem2>(a: &'a Iter1, b: &'b Iter2) -> usize
+pub fn jaro_winkler_pair(a: &str, b: &str) -> (f64, f64) {
+    let (a, b) = (StringWrapper(a), StringWrapper(b));
+    let sim = generic_jaro(&a, &b);
+    (
+        sim,
+        winkler_boost(&a, &b, sim, |a_char, b_char| a_char == b_char),
+    )
+}
+
+/// Like Jaro-Winkler, but the characters of the common prefix are compared
+/// using `eq`. This allows e.g. a case-insensitive prefix boost, while the
+/// Jaro similarity itself still compares characters exactly.
+///
+/// ```
+/// use strsim::{jaro_winkler, jaro_winkler_prefix_by};
+///
+/// let ignore_case = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+/// assert_eq!(
+///     jaro_winkler("McDonald", "McDonald"),
+///     jaro_winkler_prefix_by("McDonald", "McDonald", ignore_case)
+/// );
+/// assert!(
+///     jaro_winkler_prefix_by("MCDonald", "Mcdonald", ignore_case)
+///         > jaro_winkler("MCDonald", "Mcdonald")
+/// );
+/// ```
+pub fn jaro_winkler_prefix_by<F>(a: &str, b: &str, eq: F) -> f64
+where
+    F: Fn(char, char) -> bool,
+{
+    let (a, b) = (StringWrapper(a), StringWrapper(b));
+    let sim = generic_jaro(&a, &b);
+    winkler_boost(&a, &b, sim, |&a_char, &b_char| eq(a_char, b_char))
+}
+
+/// The Levenshtein distance of two sequences. `should_cancel` is called
+/// before every row of the distance matrix, and as soon as it returns `true`
+/// the calculation is aborted and `None` is returned.
+fn levenshtein_rows<'a, 'b, Iter1, Iter2, Elem1, Elem2, F>(
+    a: &'a Iter1,
+    b: &'b Iter2,
+    mut should_cancel: F,
+) -> Option<usize>
 where
+    Iter1: ?Sized,
+    Iter2: ?Sized,
     &'a Iter1: IntoIterator<Item = Elem1>,
     &'b Iter2: IntoIterator<Item = Elem2>,
     Elem1: PartialEq<Elem2>,
+    F: FnMut() -> bool,
 {
     let b_len = b.into_iter().count();
 
@@ -243,6 +630,10 @@ where
     let mut result = b_len;
 
     for (i, a_elem) in a.into_iter().enumerate() {
+        if should_cancel() {
+            return None;
+        }
+
         result = i + 1;
         let mut distance_b = i;
 
@@ -255,7 +646,26 @@ where
         }
     }
 
-    result
+    Some(result)
+}
+
+/// Calculates the minimum number of insertions, deletions, and substitutions
+/// required to change one sequence into the other.
+///
+/// ```
+/// use strsim::generic_levenshtein;
+///
+/// assert_eq!(3, generic_levenshtein(&[1,2,3], &[1,2,3,4,5,6]));
+/// ```
+pub fn generic_levenshtein<'a, 'b, Iter1, Iter2, Elem1, Elem2>(a: &'a Iter1, b: &'b Iter2) -> usize
+where
+    Iter1: ?Sized,
+    Iter2: ?Sized,
+    &'a Iter1: IntoIterator<Item = Elem1>,
+    &'b Iter2: IntoIterator<Item = Elem2>,
+    Elem1: PartialEq<Elem2>,
+{
+    levenshtein_rows(a, b, || false).expect("the calculation is never cancelled")
 }
 
 /// Calculates the minimum number of insertions, deletions, and substitutions
@@ -289,45 +699,51 @@ pub fn normalized_levenshtein(a: &str, b: &str) -> f64 {
     1.0 - (levenshtein(a, b) as f64) / (a.chars().count().max(b.chars().count()) as f64)
 }
 
-/// Like Levenshtein but allows for adjacent transpositions. Each substring can
-/// only be edited once.
-///
-/// ```
-/// use strsim::osa_distance;
-///
-/// assert_eq!(3, osa_distance("ab", "bca"));
-/// ```
-pub fn osa_distance(a: &str, b: &str) -> usize {
-    let b_len = b.chars().count();
+/// The optimal string alignment distance of two sequences. The previous
+/// elements are tracked while iterating, so `a` and `b` are only traversed
+/// and never collected.
+fn osa_impl<'a, 'b, Iter1, Iter2, Elem>(a: &'a Iter1, b: &'b Iter2) -> usize
+where
+    Iter1: ?Sized,
+    Iter2: ?Sized,
+    &'a Iter1: IntoIterator<Item = Elem>,
+    &'b Iter2: IntoIterator<Item = Elem>,
+    Elem: PartialEq + Copy,
+{
+    let b_len = b.into_iter().count();
     // 0..=b_len behaves like 0..b_len.saturating_add(1) which could be a different size
     // this leads to significantly worse code gen when swapping the vectors below
     let mut prev_two_distances: Vec<usize> = (0..b_len + 1).collect();
     let mut prev_distances: Vec<usize> = (0..b_len + 1).collect();
     let mut curr_distances: Vec<usize> = vec![0; b_len + 1];
 
-    let mut prev_a_char = char::MAX;
-    let mut prev_b_char = char::MAX;
+    let mut prev_a_elem = None;
+    let mut prev_b_elem = None;
 
-    for (i, a_char) in a.chars().enumerate() {
+    for (i, a_elem) in a.into_iter().enumerate() {
         curr_distances[0] = i + 1;
 
-        for (j, b_char) in b.chars().enumerate() {
-            let cost = usize::from(a_char != b_char);
+        for (j, b_elem) in b.into_iter().enumerate() {
+            let cost = usize::from(a_elem != b_elem);
             curr_distances[j + 1] = min(
                 curr_distances[j] + 1,
                 min(prev_distances[j + 1] + 1, prev_distances[j] + cost),
             );
-            if i > 0 && j > 0 && a_char != b_char && a_char == prev_b_char && b_char == prev_a_char
+            if i > 0
+                && j > 0
+                && a_elem != b_elem
+                && prev_b_elem == Some(a_elem)
+                && prev_a_elem == Some(b_elem)
             {
                 curr_distances[j + 1] = min(curr_distances[j + 1], prev_two_distances[j - 1] + 1);
             }
 
-            prev_b_char = b_char;
+            prev_b_elem = Some(b_elem);
         }
 
         mem::swap(&mut prev_two_distances, &mut prev_distances);
         mem::swap(&mut prev_distances, &mut curr_distances);
-        prev_a_char = a_char;
+        prev_a_elem = Some(a_elem);
     }
 
     // access prev_distances instead of curr_distances since we swapped
@@ -336,6 +752,30 @@ pub fn osa_distance(a: &str, b: &str) -> usize {
     prev_distances[b_len]
 }
 
+/// Like Levenshtein but allows for adjacent transpositions. Each substring can
+/// only be edited once. Only equality of the elements is required.
+///
+/// ```
+/// use strsim::generic_osa;
+///
+/// assert_eq!(1, generic_osa(&[1, 2, 3], &[1, 3, 2]));
+/// ```
+pub fn generic_osa<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    osa_impl(a, b)
+}
+
+/// Like Levenshtein but allows for adjacent transpositions. Each substring can
+/// only be edited once.
+///
+/// ```
+/// use strsim::osa_distance;
+///
+/// assert_eq!(3, osa_distance("ab", "bca"));
+/// ```
+pub fn osa_distance(a: &str, b: &str) -> usize {
+    osa_impl(&StringWrapper(a), &StringWrapper(b))
+}
+
 /* Returns the final index for a value in a single vector that represents a fixed
 2d grid */
 fn flat_index(i: usize, j: usize, width: usize) -> usize {
@@ -352,7 +792,7 @@ fn flat_index(i: usize, j: usize, width: usize) -> usize {
 /// ```
 pub fn generic_damerau_levenshtein<Elem>(a_elems: &[Elem], b_elems: &[Elem]) -> usize
 where
-    Elem: Eq + Hash + Clone,
+    Elem: Eq + Hash,
 {
     let a_len = a_elems.len();
     let b_len = b_elems.len();
@@ -379,7 +819,7 @@ where
         distances[flat_index(1, j + 1, width)] = j;
     }
 
-    let mut elems: HashMap<Elem, usize> = HashMap::with_capacity(64);
+    let mut elems: HashMap<&Elem, usize> = HashMap::with_capacity(64);
 
     for i in 1..(a_len + 1) {
         let mut db = 0;
@@ -407,7 +847,7 @@ where
             );
         }
 
-        elems.insert(a_elems[i - 1].clone(), i);
+        elems.insert(&a_elems[i - 1], i);
     }
 
     distances[flat_index(a_len + 1, b_len + 1, width)]
@@ -701,6 +1141,22 @@ pub fn normalized_damerau_levenshtein(a: &str, b: &str) -> f64 {
     1.0 - (dist as f64) / (max(len1, len2) as f64)
 }
 
+/// Calculates the Damerau-Levenshtein similarity of two strings as a whole
+/// percentage between 0 and 100, i.e. [`normalized_damerau_levenshtein`]
+/// scaled and rounded. Swapped adjacent characters only count as a single
+/// edit, so inputs with many transpositions score higher than with
+/// [`levenshtein_percent`].
+///
+/// ```
+/// use strsim::{damerau_ratio, levenshtein_percent};
+///
+/// assert_eq!(75, damerau_ratio("abcd", "abdc"));
+/// assert_eq!(50.0, levenshtein_percent("abcd", "abdc", 0));
+/// ```
+pub fn damerau_ratio(a: &str, b: &str) -> u8 {
+    (normalized_damerau_levenshtein(a, b) * 100.0).round() as u8
+}
+
 /// Returns an Iterator of char tuples.
 fn bigrams(s: &str) -> impl Iterator<Item = (char, char)> + '_ {
     s.chars().zip(s.chars().skip(1))
@@ -753,555 +1209,7840 @@ pub fn sorensen_dice(a: &str, b: &str) -> f64 {
     (2 * intersection_size) as f64 / (a.len() + b.len() - 2) as f64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Counts the occurrences of every `n`-gram of characters in `chars`.
+fn ngram_counts(chars: &[char], n: usize) -> HashMap<&[char], usize> {
+    let mut counts = HashMap::new();
+    if n == 0 {
+        return counts;
+    }
 
-    macro_rules! assert_delta {
-        ($x:expr, $y:expr) => {
-            assert_delta!($x, $y, 1e-5);
-        };
-        ($x:expr, $y:expr, $d:expr) => {
-            if ($x - $y).abs() > $d {
-                panic!(
-                    "assertion failed: actual: `{}`, expected: `{}`: \
-                    actual not within < {} of expected",
-                    $x, $y, $d
-                );
-            }
-        };
+    for gram in chars.windows(n) {
+        *counts.entry(gram).or_insert(0) += 1;
     }
+    counts
+}
 
-    #[test]
-    fn bigrams_iterator() {
-        let mut bi = bigrams("abcde");
+/// Calculates the number of character `n`-grams the two strings have in
+/// common. Repeated `n`-grams are only counted as often as they occur in
+/// both strings (multiset intersection). This is the numerator of the
+/// Sørensen-Dice coefficient without any normalization. Returns an error if
+/// `n` is 0.
+///
+/// ```
+/// use strsim::shared_ngrams;
+///
+/// assert_eq!(Ok(1), shared_ngrams("night", "nacht", 2));
+/// assert_eq!(Ok(2), shared_ngrams("aaa", "aaaa", 2));
+/// ```
+pub fn shared_ngrams(a: &str, b: &str, n: usize) -> Result<usize, StrSimError> {
+    check_ngram_size(n)?;
 
-        assert_eq!(Some(('a', 'b')), bi.next());
-        assert_eq!(Some(('b', 'c')), bi.next());
-        assert_eq!(Some(('c', 'd')), bi.next());
-        assert_eq!(Some(('d', 'e')), bi.next());
-        assert_eq!(None, bi.next());
-    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
 
-    fn assert_hamming_dist(dist: usize, str1: &str, str2: &str) {
-        assert_eq!(Ok(dist), hamming(str1, str2));
-    }
+    let mut a_ngrams = ngram_counts(&a, n);
+    let mut intersection_size = 0;
 
-    #[test]
-    fn hamming_empty() {
-        assert_hamming_dist(0, "", "")
+    for (gram, b_count) in ngram_counts(&b, n) {
+        if let Some(a_count) = a_ngrams.get_mut(gram) {
+            let shared = min(*a_count, b_count);
+            *a_count -= shared;
+            intersection_size += shared;
+        }
     }
 
-    #[test]
-    fn hamming_same() {
-        assert_hamming_dist(0, "hamming", "hamming")
-    }
+    Ok(intersection_size)
+}
 
-    #[test]
-    fn hamming_numbers() {
-        assert_eq!(Ok(1), generic_hamming(&[1, 2, 4], &[1, 2, 3]));
-    }
+/// The result of every string metric in this crate for a single pair of
+/// strings. See [`all_scores`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scores {
+    /// `None` if the strings have different lengths.
+    pub hamming: Option<usize>,
+    pub levenshtein: usize,
+    pub normalized_levenshtein: f64,
+    pub osa_distance: usize,
+    pub damerau_levenshtein: usize,
+    pub normalized_damerau_levenshtein: f64,
+    pub jaro: f64,
+    pub jaro_winkler: f64,
+    pub sorensen_dice: f64,
+}
 
-    #[test]
-    fn hamming_diff() {
-        assert_hamming_dist(3, "hamming", "hammers")
+/// Calculates every string metric in this crate for the two strings. This is
+/// mostly useful to compare the behavior of the different metrics.
+///
+/// ```
+/// use strsim::all_scores;
+///
+/// let scores = all_scores("kitten", "sitting");
+/// assert_eq!(None, scores.hamming);
+/// assert_eq!(3, scores.levenshtein);
+/// ```
+pub fn all_scores(a: &str, b: &str) -> Scores {
+    let (jaro, jaro_winkler) = jaro_winkler_pair(a, b);
+
+    Scores {
+        hamming: hamming(a, b).ok(),
+        levenshtein: levenshtein(a, b),
+        normalized_levenshtein: normalized_levenshtein(a, b),
+        osa_distance: osa_distance(a, b),
+        damerau_levenshtein: damerau_levenshtein(a, b),
+        normalized_damerau_levenshtein: normalized_damerau_levenshtein(a, b),
+        jaro,
+        jaro_winkler,
+        sorensen_dice: sorensen_dice(a, b),
     }
+}
 
-    #[test]
-    fn hamming_diff_multibyte() {
-        assert_hamming_dist(2, "hamming", "h香mmüng");
+/// The character class mapping used by [`levenshtein_classed`]. All digits are
+/// mapped to `'0'`, punctuation is removed (`None`) and every other character
+/// is kept unchanged.
+///
+/// ```
+/// use strsim::char_class;
+///
+/// assert_eq!(Some('0'), char_class('7'));
+/// assert_eq!(None, char_class('.'));
+/// assert_eq!(Some('a'), char_class('a'));
+/// ```
+pub fn char_class(c: char) -> Option<char> {
+    if c.is_ascii_digit() {
+        Some('0')
+    } else if c.is_ascii_punctuation() {
+        None
+    } else {
+        Some(c)
+    }
+}
+
+/// Like Levenshtein, but every character is first mapped through `class`.
+/// Characters mapped to `None` are removed before comparing.
+///
+/// ```
+/// use strsim::levenshtein_classed_by;
+///
+/// let vowels = |c: char| if "aeiou".contains(c) { Some('a') } else { Some(c) };
+/// assert_eq!(0, levenshtein_classed_by("bet", "bit", vowels));
+/// ```
+pub fn levenshtein_classed_by<F>(a: &str, b: &str, class: F) -> usize
+where
+    F: Fn(char) -> Option<char>,
+{
+    let a: Vec<char> = a.chars().filter_map(&class).collect();
+    let b: Vec<char> = b.chars().filter_map(&class).collect();
+    generic_levenshtein(&a, &b)
+}
+
+/// Like Levenshtein, but all digits are treated as equal and punctuation is
+/// ignored. See [`char_class`] for the exact mapping.
+///
+/// ```
+/// use strsim::levenshtein_classed;
+///
+/// assert_eq!(0, levenshtein_classed("123 Main St.", "987 Main St"));
+/// ```
+pub fn levenshtein_classed(a: &str, b: &str) -> usize {
+    levenshtein_classed_by(a, b, char_class)
+}
+
+/// Calculates the minimum number of insertions, deletions, and substitutions
+/// required to change one `OsStr` into the other, without requiring them to be
+/// valid UTF-8.
+///
+/// On Unix the raw bytes are compared, on Windows the UTF-16 code units. On
+/// other platforms the strings are converted lossily and compared by `char`.
+///
+/// ```
+/// use std::ffi::OsStr;
+/// use strsim::levenshtein_os;
+///
+/// assert_eq!(3, levenshtein_os(OsStr::new("kitten"), OsStr::new("sitting")));
+/// ```
+pub fn levenshtein_os(a: &OsStr, b: &OsStr) -> usize {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        generic_levenshtein(a.as_bytes(), b.as_bytes())
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        let a: Vec<u16> = a.encode_wide().collect();
+        let b: Vec<u16> = b.encode_wide().collect();
+        generic_levenshtein(&a, &b)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        levenshtein(&a.to_string_lossy(), &b.to_string_lossy())
+    }
+}
+
+/// Returns the key of `map` with the smallest Levenshtein distance to `query`,
+/// or `None` if no key is within `max_distance`. Ties are resolved in favor
+/// of the lexicographically smaller key, so the result does not depend on the
+/// iteration order of the map.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use strsim::closest_key;
+///
+/// let mut options = HashMap::new();
+/// options.insert("verbose".to_string(), true);
+/// options.insert("version".to_string(), false);
+///
+/// assert_eq!(Some(&"verbose".to_string()), closest_key("verbsoe", &options, 2));
+/// assert_eq!(None, closest_key("quiet", &options, 2));
+/// ```
+pub fn closest_key<'a, V>(
+    query: &str,
+    map: &'a HashMap<String, V>,
+    max_distance: usize,
+) -> Option<&'a String> {
+    map.keys()
+        .map(|key| (levenshtein(query, key), key))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, key)| key)
+}
+
+/// Calculates the Jaccard similarity of the sets of character `n`-grams of the
+/// two strings. Strings without any `n`-grams are only considered similar if
+/// they are identical. Returns an error if `n` is 0.
+///
+/// ```
+/// use strsim::{jaccard, StrSimError};
+///
+/// assert_eq!(Ok(1.0), jaccard("night", "night", 2));
+/// assert!((jaccard("night", "nacht", 2).unwrap() - 1.0 / 7.0).abs() < 0.00001);
+/// assert_eq!(Err(StrSimError::InvalidNgramSize), jaccard("night", "nacht", 0));
+/// ```
+pub fn jaccard(a: &str, b: &str, n: usize) -> Result<f64, StrSimError> {
+    check_ngram_size(n)?;
+
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+
+    let a_set: HashSet<&[char]> = ngram_counts(&a_chars, n).into_keys().collect();
+    let b_set: HashSet<&[char]> = ngram_counts(&b_chars, n).into_keys().collect();
+    Ok(set_jaccard(&a_set, &b_set, a == b))
+}
+
+/// Hashes every `n`-gram of characters using a polynomial rolling hash.
+fn rolling_ngram_hashes(s: &str, n: usize) -> HashSet<u64> {
+    const BASE: u64 = 0x0100_0000_01b3;
+
+    let mut hashes = HashSet::new();
+    if n == 0 {
+        return hashes;
+    }
+
+    // BASE^(n-1) is used to remove the outgoing character from the hash
+    let mut outgoing_factor = 1_u64;
+    for _ in 1..n {
+        outgoing_factor = outgoing_factor.wrapping_mul(BASE);
+    }
+
+    let mut hash = 0_u64;
+    let mut outgoing = s.chars();
+    for (i, c) in s.chars().enumerate() {
+        if i >= n {
+            let removed = outgoing.next().expect("outgoing lags n chars behind");
+            hash = hash.wrapping_sub(u64::from(removed).wrapping_mul(outgoing_factor));
+        }
+        hash = hash.wrapping_mul(BASE).wrapping_add(u64::from(c));
+        if i + 1 >= n {
+            hashes.insert(hash);
+        }
+    }
+    hashes
+}
+
+/// Calculates the Jaccard similarity of two sets. Two empty sets are only
+/// considered similar if the strings they were built from are `identical`.
+fn set_jaccard<T: Eq + Hash>(a: &HashSet<T>, b: &HashSet<T>, identical: bool) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return if identical { 1.0 } else { 0.0 };
+    }
+
+    let intersection = a.intersection(b).count();
+    intersection as f64 / (a.len() + b.len() - intersection) as f64
+}
+
+/// Like [`jaccard`], but every `n`-gram is reduced to a 64 bit rolling hash
+/// instead of being stored. This uses considerably less memory for large
+/// inputs.
+///
+/// Different `n`-grams can produce the same hash, in which case they are
+/// counted as equal. The chance of this happening is tiny, but the result is
+/// therefore an approximation of [`jaccard`] rather than an exact value.
+/// Returns an error if `n` is 0.
+///
+/// ```
+/// use strsim::{jaccard, jaccard_hashed};
+///
+/// assert_eq!(jaccard("night", "nacht", 2), jaccard_hashed("night", "nacht", 2));
+/// ```
+pub fn jaccard_hashed(a: &str, b: &str, n: usize) -> Result<f64, StrSimError> {
+    check_ngram_size(n)?;
+
+    Ok(set_jaccard(
+        &rolling_ngram_hashes(a, n),
+        &rolling_ngram_hashes(b, n),
+        a == b,
+    ))
+}
+
+/// Mixes the bits of `x` (the finalizer of SplitMix64), used to derive
+/// independent hash functions for MinHash.
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Calculates the MinHash signature of a set of `n`-gram hashes: for each of
+/// the `num_hashes` hash functions the minimum hash of any element. A set
+/// without elements has an empty signature.
+fn minhash_signature(hashes: &HashSet<u64>, num_hashes: usize) -> Vec<u64> {
+    if hashes.is_empty() {
+        return Vec::new();
+    }
+    (0..num_hashes as u64)
+        .map(|i| {
+            let seed = mix64(i);
+            hashes.iter().map(|&hash| mix64(hash ^ seed)).min().unwrap()
+        })
+        .collect()
+}
+
+/// Estimates the share of hash functions for which two MinHash signatures
+/// agree. An empty signature is not similar to anything.
+fn minhash_agreement(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len() as f64
+}
+
+/// Estimates the [`jaccard`] similarity of the character `n`-grams of the two
+/// strings with MinHash. Each of the `num_hashes` hash functions picks the
+/// `n`-gram with the smallest hash in either string, and the chance that both
+/// strings pick the same one equals their Jaccard similarity. The estimate is
+/// the share of hash functions on which the strings agree.
+///
+/// Comparing the signatures takes `O(num_hashes)` instead of time
+/// proportional to the string lengths; see [`MinHashSignature`] for
+/// reusing them across many comparisons. In exchange the result is only an
+/// estimate: its standard error is `sqrt(J * (1 - J) / num_hashes)` for a true
+/// similarity `J`, so at most `0.5 / sqrt(num_hashes)`, e.g. 0.03 for 256
+/// hash functions. The hash functions are fixed, so the estimate is
+/// deterministic.
+///
+/// Identical strings always score 1.0, while other strings without any
+/// `n`-grams score 0.0, matching [`jaccard`]. Returns an error if `n` or
+/// `num_hashes` is 0.
+///
+/// ```
+/// use strsim::{jaccard, minhash_similarity};
+///
+/// let a = "the quick brown fox jumps over the lazy dog";
+/// let b = "the quick brown fox jumped over the lazy cat";
+/// let estimate = minhash_similarity(a, b, 3, 256).unwrap();
+/// assert!((estimate - jaccard(a, b, 3).unwrap()).abs() < 0.1);
+/// ```
+pub fn minhash_similarity(
+    a: &str,
+    b: &str,
+    n: usize,
+    num_hashes: usize,
+) -> Result<f64, StrSimError> {
+    check_ngram_size(n)?;
+    if num_hashes == 0 {
+        return Err(StrSimError::InvalidHashCount);
+    }
+
+    if a == b {
+        return Ok(1.0);
+    }
+    let a = MinHashSignature::new(a, n, num_hashes)?;
+    let b = MinHashSignature::new(b, n, num_hashes)?;
+    Ok(a.similarity(&b))
+}
+
+/// The MinHash signature of a string, for estimating the [`jaccard`]
+/// similarity of many pairs of strings. Building a signature takes time
+/// proportional to the length of the string times `num_hashes`, but
+/// comparing two signatures only takes `O(num_hashes)`. See
+/// [`minhash_similarity`] for the accuracy of the estimate.
+///
+/// ```
+/// use strsim::MinHashSignature;
+///
+/// let docs = ["the quick brown fox", "the quick brown dog", "lorem ipsum"];
+/// let signatures: Vec<MinHashSignature> = docs
+///     .iter()
+///     .map(|doc| MinHashSignature::new(doc, 3, 128).unwrap())
+///     .collect();
+/// assert!(signatures[0].similarity(&signatures[1]) > signatures[0].similarity(&signatures[2]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHashSignature {
+    n: usize,
+    num_hashes: usize,
+    // empty if the string has no n-grams
+    mins: Vec<u64>,
+}
+
+impl MinHashSignature {
+    /// Calculates the signature of the character `n`-grams of `s` using
+    /// `num_hashes` hash functions. Returns an error if `n` or `num_hashes`
+    /// is 0.
+    pub fn new(s: &str, n: usize, num_hashes: usize) -> Result<Self, StrSimError> {
+        check_ngram_size(n)?;
+        if num_hashes == 0 {
+            return Err(StrSimError::InvalidHashCount);
+        }
+
+        Ok(MinHashSignature {
+            n,
+            num_hashes,
+            mins: minhash_signature(&rolling_ngram_hashes(s, n), num_hashes),
+        })
+    }
+
+    /// Estimates the Jaccard similarity of the strings the two signatures
+    /// were built from. A string without any `n`-grams is not similar to
+    /// anything, not even to itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures were built with a different `n` or number
+    /// of hash functions.
+    pub fn similarity(&self, other: &MinHashSignature) -> f64 {
+        assert!(
+            self.n == other.n && self.num_hashes == other.num_hashes,
+            "signatures must use the same n-gram size and number of hashes"
+        );
+        minhash_agreement(&self.mins, &other.mins)
+    }
+}
+
+/// A single step of an edit script turning one string into another. See
+/// [`operations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOp {
+    /// The token is the same in both strings.
+    Match(String),
+    /// The token of the first string is replaced by the token of the second.
+    Substitute(String, String),
+    /// The token is inserted into the first string.
+    Insert(String),
+    /// The token is removed from the first string.
+    Delete(String),
+}
+
+/// The granularity at which [`operations_with_unit`] compares strings.
+///
+/// The enum is non-exhaustive because the available variants depend on the
+/// enabled features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Unit {
+    /// Every `char` is a token.
+    Char,
+    /// Every extended grapheme cluster is a token.
+    #[cfg(feature = "unicode")]
+    Grapheme,
+    /// Every whitespace separated word is a token.
+    Word,
+}
+
+impl Unit {
+    fn tokenize(self, s: &str) -> Vec<&str> {
+        match self {
+            Unit::Char => s
+                .char_indices()
+                .map(|(i, c)| &s[i..i + c.len_utf8()])
+                .collect(),
+            #[cfg(feature = "unicode")]
+            Unit::Grapheme => s.graphemes(true).collect(),
+            Unit::Word => s.split_whitespace().collect(),
+        }
+    }
+}
+
+/// Calculates a Levenshtein edit script over two token slices by tracing back
+/// through the full distance matrix.
+fn levenshtein_operations(a: &[&str], b: &[&str]) -> Vec<EditOp> {
+    let width = b.len() + 1;
+    let mut distances = vec![0; (a.len() + 1) * width];
+
+    for i in 0..=a.len() {
+        distances[i * width] = i;
+    }
+    for (j, distance) in distances.iter_mut().take(width).enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            distances[i * width + j] = min(
+                distances[(i - 1) * width + j - 1] + cost,
+                min(
+                    distances[(i - 1) * width + j] + 1,
+                    distances[i * width + j - 1] + 1,
+                ),
+            );
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+    while i > 0 || j > 0 {
+        let current = distances[i * width + j];
+        if i > 0 && j > 0 {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            if current == distances[(i - 1) * width + j - 1] + cost {
+                ops.push(if cost == 0 {
+                    EditOp::Match(a[i - 1].to_string())
+                } else {
+                    EditOp::Substitute(a[i - 1].to_string(), b[j - 1].to_string())
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if i > 0 && current == distances[(i - 1) * width + j] + 1 {
+            ops.push(EditOp::Delete(a[i - 1].to_string()));
+            i -= 1;
+        } else {
+            ops.push(EditOp::Insert(b[j - 1].to_string()));
+            j -= 1;
+        }
+    }
+
+    ops.reverse();
+    ops
+}
+
+/// Calculates a minimal sequence of edit operations turning `a` into `b`,
+/// comparing the strings character by character. The number of operations
+/// other than [`EditOp::Match`] equals the Levenshtein distance.
+///
+/// ```
+/// use strsim::{operations, EditOp};
+///
+/// assert_eq!(
+///     vec![
+///         EditOp::Substitute("k".into(), "s".into()),
+///         EditOp::Match("a".into()),
+///         EditOp::Insert("t".into()),
+///     ],
+///     operations("ka", "sat")
+/// );
+/// ```
+pub fn operations(a: &str, b: &str) -> Vec<EditOp> {
+    operations_with_unit(a, b, Unit::Char)
+}
+
+/// Like [`operations`], but the strings are split into tokens according to
+/// `unit` first. This allows e.g. word level diffs.
+///
+/// ```
+/// use strsim::{operations_with_unit, EditOp, Unit};
+///
+/// assert_eq!(
+///     vec![
+///         EditOp::Match("the".into()),
+///         EditOp::Substitute("quick".into(), "slow".into()),
+///         EditOp::Match("fox".into()),
+///     ],
+///     operations_with_unit("the quick fox", "the slow fox", Unit::Word)
+/// );
+/// ```
+pub fn operations_with_unit(a: &str, b: &str, unit: Unit) -> Vec<EditOp> {
+    levenshtein_operations(&unit.tokenize(a), &unit.tokenize(b))
+}
+
+/// Builds the aligned first string, the marker line and the aligned second
+/// string from the [`operations`] turning `a` into `b`.
+fn alignment_rows(a: &str, b: &str, gap: char) -> (String, String, String) {
+    let (mut top, mut markers, mut bottom) = (String::new(), String::new(), String::new());
+    for op in operations(a, b) {
+        let (x, marker, y) = match op {
+            EditOp::Match(t) => (t.clone(), '|', t),
+            EditOp::Substitute(x, y) => (x, '.', y),
+            EditOp::Insert(t) => (gap.to_string(), ' ', t),
+            EditOp::Delete(t) => (t, ' ', gap.to_string()),
+        };
+        top.push_str(&x);
+        markers.push(marker);
+        bottom.push_str(&y);
+    }
+    (top, markers, bottom)
+}
+
+/// Aligns two strings along a minimal Levenshtein edit script, inserting
+/// `gap` wherever one string has a character without counterpart in the
+/// other. Both returned strings have the same number of characters.
+///
+/// ```
+/// use strsim::align;
+///
+/// assert_eq!(
+///     ("kitten-".to_string(), "sitting".to_string()),
+///     align("kitten", "sitting", '-')
+/// );
+/// ```
+pub fn align(a: &str, b: &str, gap: char) -> (String, String) {
+    let (top, _, bottom) = alignment_rows(a, b, gap);
+    (top, bottom)
+}
+
+/// Renders the [`align`]ment of two strings as three lines for display: the
+/// aligned `a`, a marker line with `|` for matching characters, `.` for
+/// substitutions and a space for gaps, and the aligned `b`. The lines are
+/// separated by `\n` and only line up in a terminal if every character has
+/// the same display width.
+///
+/// ```
+/// use strsim::alignment_string;
+///
+/// assert_eq!(
+///     "kitten-\n.|||.| \nsitting",
+///     alignment_string("kitten", "sitting", '-')
+/// );
+/// ```
+pub fn alignment_string(a: &str, b: &str, gap: char) -> String {
+    let (top, markers, bottom) = alignment_rows(a, b, gap);
+    format!("{}\n{}\n{}", top, markers, bottom)
+}
+
+/// Calculates the Indel distance of two strings, i.e. the minimum number of
+/// insertions and deletions of characters turning `a` into `b`. Unlike
+/// Levenshtein, a substitution costs two edits.
+///
+/// ```
+/// use strsim::indel_distance;
+///
+/// assert_eq!(2, indel_distance("abc", "axc"));
+/// assert_eq!(5, indel_distance("kitten", "sitting"));
+/// ```
+pub fn indel_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    a.len() + b.len() - 2 * lcs_len(&a, &b)
+}
+
+/// Like [`operations`], but only insertions and deletions are allowed, so
+/// the script never contains [`EditOp::Substitute`]. The number of
+/// operations other than [`EditOp::Match`] equals the [`indel_distance`].
+/// Where a character is replaced, it is deleted before the new one is
+/// inserted.
+///
+/// ```
+/// use strsim::{indel_operations, EditOp};
+///
+/// assert_eq!(
+///     vec![
+///         EditOp::Match("a".into()),
+///         EditOp::Delete("b".into()),
+///         EditOp::Insert("x".into()),
+///         EditOp::Match("c".into()),
+///     ],
+///     indel_operations("abc", "axc")
+/// );
+/// ```
+pub fn indel_operations(a: &str, b: &str) -> Vec<EditOp> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let suffix_lens = lcs_suffix_lens(&a, &b);
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(EditOp::Match(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if suffix_lens[i + 1][j] >= suffix_lens[i][j + 1] {
+            ops.push(EditOp::Delete(a[i].to_string()));
+            i += 1;
+        } else {
+            ops.push(EditOp::Insert(b[j].to_string()));
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().map(|c| EditOp::Delete(c.to_string())));
+    ops.extend(b[j..].iter().map(|c| EditOp::Insert(c.to_string())));
+    ops
+}
+
+/// Checks whether the two strings are at most a single insertion, deletion, or
+/// substitution apart. This is equivalent to `levenshtein(a, b) <= 1`, but
+/// runs in linear time.
+///
+/// ```
+/// use strsim::is_one_edit_away;
+///
+/// assert!(is_one_edit_away("pale", "ple"));
+/// assert!(is_one_edit_away("pale", "pale"));
+/// assert!(!is_one_edit_away("pale", "bake"));
+/// ```
+pub fn is_one_edit_away(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    // the suffix must not overlap with the prefix in either string
+    let max_suffix = min(a.len(), b.len()) - prefix;
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take(max_suffix)
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    a.len() - prefix - suffix <= 1 && b.len() - prefix - suffix <= 1
+}
+
+/// Returns the distinct character `n`-grams of `s`.
+fn distinct_ngrams(s: &str, n: usize) -> HashSet<String> {
+    let chars: Vec<char> = s.chars().collect();
+    ngram_counts(&chars, n)
+        .into_keys()
+        .map(|gram| gram.iter().collect())
+        .collect()
+}
+
+/// An inverted index from character `n`-grams to the documents containing
+/// them. It is used to quickly find candidates sharing enough `n`-grams with
+/// a query before scoring them with a more expensive metric.
+///
+/// ```
+/// use strsim::NgramIndex;
+///
+/// let mut index = NgramIndex::new(2);
+/// index.insert(1, "apple");
+/// index.insert(2, "apply");
+/// index.insert(3, "banana");
+///
+/// assert_eq!(vec![1, 2], index.query("appel", 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NgramIndex<Id> {
+    n: usize,
+    ids: Vec<Id>,
+    postings: HashMap<String, Vec<usize>>,
+}
+
+impl<Id: Clone> NgramIndex<Id> {
+    /// Creates an empty index over character `n`-grams.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            ids: Vec::new(),
+            postings: HashMap::new(),
+        }
+    }
+
+    /// Adds the document `text` to the index, identified by `id`.
+    pub fn insert(&mut self, id: Id, text: &str) {
+        let doc = self.ids.len();
+        self.ids.push(id);
+
+        for gram in distinct_ngrams(text, self.n) {
+            self.postings.entry(gram).or_default().push(doc);
+        }
+    }
+
+    /// Returns the ids of all documents sharing at least `min_shared`
+    /// distinct `n`-grams with `text`, in insertion order. A `min_shared` of 0
+    /// returns all documents.
+    pub fn query(&self, text: &str, min_shared: usize) -> Vec<Id> {
+        let mut shared = vec![0_usize; self.ids.len()];
+
+        for gram in distinct_ngrams(text, self.n) {
+            if let Some(docs) = self.postings.get(&gram) {
+                for &doc in docs {
+                    shared[doc] += 1;
+                }
+            }
+        }
+
+        shared
+            .iter()
+            .zip(&self.ids)
+            .filter(|&(&count, _)| count >= min_shared)
+            .map(|(_, id)| id.clone())
+            .collect()
+    }
+}
+
+/// Maps tokens to stable `u32` ids, so comparing token sequences only has to
+/// compare integers. The same interner can be reused for many comparisons,
+/// which amortizes the cost of interning tokens that occur repeatedly.
+///
+/// ```
+/// use strsim::Interner;
+///
+/// let mut interner = Interner::new();
+/// assert_eq!(0, interner.intern("hello"));
+/// assert_eq!(1, interner.intern("world"));
+/// assert_eq!(0, interner.intern("hello"));
+///
+/// assert_eq!(1, interner.word_levenshtein("hello world", "hello there"));
+/// assert_eq!(0, interner.line_levenshtein("a\nb", "a\nb"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `token`, assigning the next free id if the token
+    /// has not been seen before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct tokens are interned.
+    pub fn intern(&mut self, token: &str) -> u32 {
+        if let Some(&id) = self.ids.get(token) {
+            return id;
+        }
+        let id = u32::try_from(self.ids.len()).expect("too many distinct tokens");
+        self.ids.insert(token.to_string(), id);
+        id
+    }
+
+    /// Returns the number of distinct tokens interned so far.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if no tokens have been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Calculates the Levenshtein distance between two token sequences,
+    /// interning all of their tokens.
+    pub fn levenshtein<'s, Iter1, Iter2>(&mut self, a: Iter1, b: Iter2) -> usize
+    where
+        Iter1: IntoIterator<Item = &'s str>,
+        Iter2: IntoIterator<Item = &'s str>,
+    {
+        let a: Vec<u32> = a.into_iter().map(|token| self.intern(token)).collect();
+        let b: Vec<u32> = b.into_iter().map(|token| self.intern(token)).collect();
+        generic_levenshtein(&a, &b)
+    }
+
+    /// Like [`word_levenshtein`], but reuses the interned words.
+    pub fn word_levenshtein(&mut self, a: &str, b: &str) -> usize {
+        self.levenshtein(a.split_whitespace(), b.split_whitespace())
+    }
+
+    /// Like [`line_levenshtein`], but reuses the interned lines.
+    pub fn line_levenshtein(&mut self, a: &str, b: &str) -> usize {
+        self.levenshtein(a.split('\n'), b.split('\n'))
+    }
+}
+
+/// Calculates the Levenshtein distance between two token sequences. Every
+/// distinct token is mapped to an integer first, so tokens are only compared
+/// once for equality. Unlike [`Interner`], the tokens are borrowed instead of
+/// copied, as the ids are not reused.
+fn interned_levenshtein<'s, Iter1, Iter2>(a: Iter1, b: Iter2) -> usize
+where
+    Iter1: IntoIterator<Item = &'s str>,
+    Iter2: IntoIterator<Item = &'s str>,
+{
+    let mut ids: HashMap<&str, u32> = HashMap::new();
+    let mut intern = |token| {
+        let next_id = u32::try_from(ids.len()).expect("too many distinct tokens");
+        *ids.entry(token).or_insert(next_id)
+    };
+
+    let a: Vec<u32> = a.into_iter().map(&mut intern).collect();
+    let b: Vec<u32> = b.into_iter().map(&mut intern).collect();
+    generic_levenshtein(&a, &b)
+}
+
+/// Calculates the minimum number of word insertions, deletions, and
+/// substitutions required to change one text into the other. Words are
+/// separated by whitespace.
+///
+/// ```
+/// use strsim::word_levenshtein;
+///
+/// assert_eq!(2, word_levenshtein("the quick brown fox", "the slow brown dog"));
+/// ```
+pub fn word_levenshtein(a: &str, b: &str) -> usize {
+    interned_levenshtein(a.split_whitespace(), b.split_whitespace())
+}
+
+/// Calculates the minimum number of line insertions, deletions, and
+/// substitutions required to change one text into the other. Every changed
+/// line counts as a single edit, like in a line based `diff`.
+///
+/// ```
+/// use strsim::line_levenshtein;
+///
+/// let a = "fn main() {\n    println!(\"hello\");\n}";
+/// let b = "fn main() {\n    println!(\"world\");\n}";
+/// assert_eq!(1, line_levenshtein(a, b));
+/// ```
+pub fn line_levenshtein(a: &str, b: &str) -> usize {
+    interned_levenshtein(a.split('\n'), b.split('\n'))
+}
+
+/// A similarity metric between two strings, such as [`jaro_winkler`] or
+/// [`normalized_levenshtein`].
+pub type Scorer = fn(&str, &str) -> f64;
+
+/// Calculates the weighted average of several similarity metrics. The weights
+/// are normalized, so they don't need to sum up to 1.0. Returns 0.0 if there
+/// are no scorers or all weights are 0.0.
+///
+/// ```
+/// use strsim::{blend, jaro_winkler, normalized_levenshtein};
+///
+/// let score = blend(
+///     "kitten",
+///     "sitting",
+///     &[(jaro_winkler, 3.0), (normalized_levenshtein, 1.0)],
+/// );
+/// let expected = 0.75 * jaro_winkler("kitten", "sitting")
+///     + 0.25 * normalized_levenshtein("kitten", "sitting");
+/// assert!((score - expected).abs() < 0.00001);
+/// ```
+pub fn blend(a: &str, b: &str, scorers: &[(Scorer, f64)]) -> f64 {
+    let total_weight: f64 = scorers.iter().map(|&(_, weight)| weight).sum();
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+
+    scorers
+        .iter()
+        .map(|&(scorer, weight)| scorer(a, b) * weight)
+        .sum::<f64>()
+        / total_weight
+}
+
+/// Calculates the minimum number of token insertions, deletions, and
+/// substitutions required to change one tokenized string into the other.
+///
+/// ```
+/// use strsim::token_levenshtein;
+///
+/// assert_eq!(1, token_levenshtein(&["let", "x", "=", "1"], &["let", "y", "=", "1"]));
+/// ```
+pub fn token_levenshtein(a: &[&str], b: &[&str]) -> usize {
+    generic_levenshtein(a, b)
+}
+
+/// Calculates an upper bound of the Jaro-Winkler similarity of two strings
+/// with the given lengths, assuming every character of the shorter string
+/// matches without transpositions.
+fn jaro_winkler_upper_bound(a_len: usize, b_len: usize) -> f64 {
+    if a_len == 0 || b_len == 0 {
+        return if a_len == b_len { 1.0 } else { 0.0 };
+    }
+
+    let matches = min(a_len, b_len) as f64;
+    let sim = (matches / a_len as f64 + matches / b_len as f64 + 1.0) / 3.0;
+    if sim > 0.7 {
+        sim + 0.4 * (1.0 - sim)
+    } else {
+        sim
+    }
+}
+
+/// Returns all candidates with a Jaro-Winkler similarity to `query` of at
+/// least `threshold`, in their original order. Candidates which can't reach
+/// the threshold based on their length alone are skipped without calculating
+/// the similarity.
+///
+/// ```
+/// use strsim::jaro_winkler_above;
+///
+/// let candidates = ["martha", "marhta", "mark", "maria"];
+/// assert_eq!(vec!["martha", "marhta"], jaro_winkler_above("martha", &candidates, 0.9));
+/// ```
+pub fn jaro_winkler_above<'a>(
+    query: &str,
+    candidates: &'a [&'a str],
+    threshold: f64,
+) -> Vec<&'a str> {
+    let query_len = query.chars().count();
+
+    candidates
+        .iter()
+        .copied()
+        .filter(|candidate| {
+            jaro_winkler_upper_bound(query_len, candidate.chars().count()) >= threshold
+                && jaro_winkler(query, candidate) >= threshold
+        })
+        .collect()
+}
+
+/// Like Levenshtein, but `should_cancel` is called before every row of the
+/// distance matrix is calculated. As soon as it returns `true` the calculation
+/// is aborted and `None` is returned. This allows keeping interactive
+/// applications responsive when comparing very long strings.
+///
+/// ```
+/// use strsim::levenshtein_cancellable;
+///
+/// assert_eq!(Some(3), levenshtein_cancellable("kitten", "sitting", || false));
+/// assert_eq!(None, levenshtein_cancellable("kitten", "sitting", || true));
+/// ```
+pub fn levenshtein_cancellable<F>(a: &str, b: &str, should_cancel: F) -> Option<usize>
+where
+    F: Fn() -> bool,
+{
+    levenshtein_rows(&StringWrapper(a), &StringWrapper(b), should_cancel)
+}
+
+/// Calculates the Levenshtein distance using the bit-parallel algorithm by
+/// Hyyrö. `a` must not contain more than 64 characters.
+fn levenshtein_bit_parallel(a: &[char], b: &[char]) -> usize {
+    debug_assert!(a.len() <= 64);
+    if a.is_empty() {
+        return b.len();
+    }
+
+    let mut peq = HybridGrowingHashmapChar::<u64>::default();
+    for (i, &ch) in a.iter().enumerate() {
+        *peq.get_mut(ch) |= 1 << i;
+    }
+
+    let last = 1_u64 << (a.len() - 1);
+    let mut vp = !0_u64;
+    let mut vn = 0_u64;
+    let mut distance = a.len();
+
+    for &ch in b {
+        let x = peq.get(ch) | vn;
+        let d0 = ((x & vp).wrapping_add(vp) ^ vp) | x;
+        let mut hp = vn | !(d0 | vp);
+        let mut hn = d0 & vp;
+
+        if hp & last != 0 {
+            distance += 1;
+        }
+        if hn & last != 0 {
+            distance -= 1;
+        }
+
+        hp = (hp << 1) | 1;
+        hn <<= 1;
+        vp = hn | !(d0 | hp);
+        vn = hp & d0;
+    }
+
+    distance
+}
+
+/// Calculates the Levenshtein distance if it is at most `max_distance`, only
+/// filling a diagonal band of the distance matrix. Returns `None` if the
+/// distance is larger.
+fn levenshtein_banded(a: &[char], b: &[char], max_distance: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    // values outside of the band are never smaller than max_distance + 1
+    let outside = max_distance + 1;
+    let mut prev: Vec<usize> = (0..b.len() + 1)
+        .map(|j| if j <= max_distance { j } else { outside })
+        .collect();
+    let mut curr = vec![outside; b.len() + 1];
+
+    for (i, &a_char) in a.iter().enumerate() {
+        let row = i + 1;
+        let start = row.saturating_sub(max_distance);
+        let end = min(b.len(), row + max_distance);
+
+        curr[0] = if row <= max_distance { row } else { outside };
+        if start > 1 {
+            curr[start - 1] = outside;
+        }
+
+        for j in max(start, 1)..end + 1 {
+            let cost = usize::from(a_char != b[j - 1]);
+            curr[j] = min(prev[j - 1] + cost, min(prev[j] + 1, curr[j - 1] + 1)).min(outside);
+        }
+        if end < b.len() {
+            curr[end + 1] = outside;
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    if distance <= max_distance {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+/// Calculates the Levenshtein distance, choosing the algorithm based on the
+/// input:
+///
+/// - if the shorter string has at most 64 characters, a bit-parallel
+///   algorithm is used, which processes a whole column at once
+/// - if the lengths differ by at most a quarter of the longer string, the
+///   strings are likely to be close, so only a diagonal band of the distance
+///   matrix is calculated. The band starts at the length difference and is
+///   doubled until it contains the distance
+/// - otherwise the same algorithm as [`levenshtein`] is used
+///
+/// The result is always identical to [`levenshtein`].
+///
+/// ```
+/// use strsim::levenshtein_auto;
+///
+/// assert_eq!(3, levenshtein_auto("kitten", "sitting"));
+/// ```
+pub fn levenshtein_auto(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+    if short.len() <= 64 {
+        return levenshtein_bit_parallel(&short, &long);
+    }
+
+    let len_diff = long.len() - short.len();
+    if len_diff <= long.len() / 4 {
+        let mut max_distance = max(len_diff, 16);
+        loop {
+            if let Some(distance) = levenshtein_banded(&short, &long, max_distance) {
+                return distance;
+            }
+            max_distance *= 2;
+        }
+    }
+
+    generic_levenshtein(&short, &long)
+}
+
+/// Calculates the number of characters which have to be removed from or
+/// inserted into the strings to make them anagrams of each other, i.e. the
+/// sum of the absolute differences of the character counts. True anagrams
+/// have a distance of 0.
+///
+/// ```
+/// use strsim::anagram_distance;
+///
+/// assert_eq!(0, anagram_distance("listen", "silent"));
+/// assert_eq!(2, anagram_distance("listen", "silant"));
+/// ```
+pub fn anagram_distance(a: &str, b: &str) -> usize {
+    let mut counts: HashMap<char, isize> = HashMap::new();
+    for c in a.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    for c in b.chars() {
+        *counts.entry(c).or_insert(0) -= 1;
+    }
+
+    counts.values().map(|count| count.unsigned_abs()).sum()
+}
+
+/// Returns how many of each character have to be inserted into `a`, in any
+/// order, so that it contains every character of `b` at least as often as
+/// `b` does, i.e. the multiset difference of `b` and `a`. Characters `a`
+/// already has enough of are not part of the result.
+///
+/// ```
+/// use strsim::required_insertions;
+/// use std::collections::HashMap;
+///
+/// let expected: HashMap<char, usize> = [('a', 1), ('b', 1)].iter().copied().collect();
+/// assert_eq!(expected, required_insertions("abc", "aabbc"));
+/// ```
+pub fn required_insertions(a: &str, b: &str) -> HashMap<char, usize> {
+    let mut counts: HashMap<char, isize> = HashMap::new();
+    for c in b.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    for c in a.chars() {
+        *counts.entry(c).or_insert(0) -= 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(c, count)| (c, count.unsigned_abs()))
+        .collect()
+}
+
+/// Checks whether `needle` is a subsequence of `haystack`, i.e. whether it can
+/// be turned into `haystack` only by inserting characters. Returns the number
+/// of insertions required, or `None` if that isn't possible.
+///
+/// ```
+/// use strsim::subsequence_distance;
+///
+/// assert_eq!(Some(2), subsequence_distance("ace", "abcde"));
+/// assert_eq!(None, subsequence_distance("aec", "abcde"));
+/// ```
+pub fn subsequence_distance(needle: &str, haystack: &str) -> Option<usize> {
+    let mut haystack_chars = haystack.chars();
+    let mut needle_len = 0;
+
+    for needle_char in needle.chars() {
+        if !haystack_chars.any(|c| c == needle_char) {
+            return None;
+        }
+        needle_len += 1;
+    }
+
+    Some(haystack.chars().count() - needle_len)
+}
+
+/// Checks whether some substring of `haystack` is within `max_errors`
+/// insertions, deletions, and substitutions of `needle`.
+///
+/// ```
+/// use strsim::fuzzy_contains;
+///
+/// assert!(fuzzy_contains("the kittin sat on the mat", "kitten", 1));
+/// assert!(!fuzzy_contains("the kittin sat on the mat", "kitten", 0));
+/// ```
+pub fn fuzzy_contains(haystack: &str, needle: &str, max_errors: usize) -> bool {
+    let needle: Vec<char> = needle.chars().collect();
+    let needle_len = needle.len();
+    if needle_len <= max_errors {
+        return true;
+    }
+
+    // a match may start anywhere in the haystack, so the first row is all 0
+    let mut cache: Vec<usize> = (1..needle_len + 1).collect();
+
+    for haystack_char in haystack.chars() {
+        let mut diagonal = 0;
+        let mut above = 0;
+
+        for (i, &needle_char) in needle.iter().enumerate() {
+            let cost = usize::from(needle_char != haystack_char);
+            let distance = min(diagonal + cost, min(cache[i] + 1, above + 1));
+            diagonal = cache[i];
+            cache[i] = distance;
+            above = distance;
+        }
+
+        if cache[needle_len - 1] <= max_errors {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Finds the substring of `haystack` with the smallest Levenshtein distance to
+/// `needle`. Returns the character index where it starts, the character index
+/// where it ends (exclusive) and its distance, or `None` if the distance is
+/// larger than `max_errors`.
+///
+/// If several substrings have the same distance, the one ending first is
+/// returned. Among the substrings with the same end, the longest one is
+/// returned.
+///
+/// ```
+/// use strsim::fuzzy_find;
+///
+/// assert_eq!(Some((4, 10, 1)), fuzzy_find("the kittin sat on the mat", "kitten", 1));
+/// assert_eq!(None, fuzzy_find("the kittin sat on the mat", "kitten", 0));
+/// ```
+pub fn fuzzy_find(
+    haystack: &str,
+    needle: &str,
+    max_errors: usize,
+) -> Option<(usize, usize, usize)> {
+    let haystack: Vec<char> = haystack.chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+
+    // find the end of the best match. A match may start anywhere in the
+    // haystack, so the first row of the distance matrix is all 0
+    let mut cache: Vec<usize> = (1..needle.len() + 1).collect();
+    let mut best = (0, needle.len());
+
+    for (j, &haystack_char) in haystack.iter().enumerate() {
+        let mut diagonal = 0;
+        let mut above = 0;
+
+        for (i, &needle_char) in needle.iter().enumerate() {
+            let cost = usize::from(needle_char != haystack_char);
+            let distance = min(diagonal + cost, min(cache[i] + 1, above + 1));
+            diagonal = cache[i];
+            cache[i] = distance;
+            above = distance;
+        }
+
+        if let Some(&distance) = cache.last() {
+            if distance < best.1 {
+                best = (j + 1, distance);
+            }
+        }
+    }
+
+    let (end, distance) = best;
+    if distance > max_errors {
+        return None;
+    }
+
+    // find the start by aligning the reversed needle with the reversed
+    // haystack ending at `end`, this time anchored at `end`
+    let mut cache: Vec<usize> = (1..needle.len() + 1).collect();
+    let mut start = end;
+
+    for (len, &haystack_char) in haystack[..end].iter().rev().enumerate() {
+        let mut diagonal = len;
+        let mut above = len + 1;
+
+        for (i, &needle_char) in needle.iter().rev().enumerate() {
+            let cost = usize::from(needle_char != haystack_char);
+            let current = min(diagonal + cost, min(cache[i] + 1, above + 1));
+            diagonal = cache[i];
+            cache[i] = current;
+            above = current;
+        }
+
+        if cache.last().map_or(len + 1, |&current| current) == distance {
+            start = end - len - 1;
+        }
+    }
+
+    Some((start, end, distance))
+}
+
+/// Calculates the cosine similarity of the character `n`-gram count vectors of
+/// the two strings, where every `n`-gram is weighted by its inverse document
+/// frequency from `idf`. `n`-grams missing from `idf` get a weight of 1.0.
+/// Downweighting common `n`-grams makes rare shared `n`-grams count more.
+/// Returns an error if `n` is 0.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use strsim::cosine_ngram_idf;
+///
+/// let idf = HashMap::new();
+/// assert!((cosine_ngram_idf("night", "night", 2, &idf).unwrap() - 1.0).abs() < 0.00001);
+/// assert!((cosine_ngram_idf("night", "nacht", 2, &idf).unwrap() - 0.25).abs() < 0.00001);
+/// ```
+pub fn cosine_ngram_idf(
+    a: &str,
+    b: &str,
+    n: usize,
+    idf: &HashMap<String, f64>,
+) -> Result<f64, StrSimError> {
+    check_ngram_size(n)?;
+
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+
+    let weighted = |chars| -> HashMap<String, f64> {
+        ngram_counts(chars, n)
+            .into_iter()
+            .map(|(gram, count)| {
+                let gram: String = gram.iter().collect();
+                let weight = idf.get(&gram).copied().unwrap_or(1.0);
+                (gram, count as f64 * weight)
+            })
+            .collect()
+    };
+    let a_vec = weighted(&a_chars);
+    let b_vec = weighted(&b_chars);
+
+    let norm = |vec: &HashMap<String, f64>| vec.values().map(|v| v * v).sum::<f64>().sqrt();
+    let (a_norm, b_norm) = (norm(&a_vec), norm(&b_vec));
+    if a_norm == 0.0 && b_norm == 0.0 {
+        return Ok(if a == b { 1.0 } else { 0.0 });
+    }
+    if a_norm == 0.0 || b_norm == 0.0 {
+        return Ok(0.0);
+    }
+
+    let dot: f64 = a_vec
+        .iter()
+        .filter_map(|(gram, a_weight)| b_vec.get(gram).map(|b_weight| a_weight * b_weight))
+        .sum();
+    Ok(dot / (a_norm * b_norm))
+}
+
+/// Calculates the length of the longest common subsequence of two slices.
+fn lcs_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut cache = vec![0; b.len() + 1];
+
+    for a_elem in a {
+        let mut diagonal = 0;
+        for (j, b_elem) in b.iter().enumerate() {
+            let current = if a_elem == b_elem {
+                diagonal + 1
+            } else {
+                max(cache[j], cache[j + 1])
+            };
+            diagonal = cache[j + 1];
+            cache[j + 1] = current;
+        }
+    }
+
+    cache[b.len()]
+}
+
+/// Calculates the normalized Indel similarity of two strings as a percentage,
+/// i.e. how much of the two strings is covered by their longest common
+/// subsequence.
+fn indel_ratio(a: &str, b: &str) -> f64 {
+    100.0 * lcs_str_similarity(a, b)
+}
+
+/// Splits a string into its set of lowercase alphanumeric tokens, as used by
+/// [`token_set_ratio`].
+fn token_set(s: &str) -> BTreeSet<String> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Calculates the [`token_set_ratio`] of two already tokenized strings.
+fn token_set_ratio_of_sets(a: &BTreeSet<String>, b: &BTreeSet<String>) -> u8 {
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+
+    let join = |tokens: Vec<&String>| {
+        tokens
+            .into_iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let intersection = join(a.intersection(b).collect());
+    let combined_a = join(a.intersection(b).chain(a.difference(b)).collect());
+    let combined_b = join(a.intersection(b).chain(b.difference(a)).collect());
+
+    let ratio = indel_ratio(&intersection, &combined_a)
+        .max(indel_ratio(&intersection, &combined_b))
+        .max(indel_ratio(&combined_a, &combined_b));
+    ratio.round() as u8
+}
+
+/// Calculates a similarity between 0 and 100 of two strings, ignoring the
+/// order and duplicates of their words. Both strings are lowercased and split
+/// into alphanumeric tokens. The shared tokens are then compared with each
+/// string's remaining tokens, so a string whose tokens are a subset of the
+/// other's tokens scores 100. Returns 0 if either string has no tokens.
+///
+/// This is the `token_set_ratio` known from fuzzywuzzy and rapidfuzz.
+///
+/// ```
+/// use strsim::token_set_ratio;
+///
+/// assert_eq!(100, token_set_ratio("fuzzy was a bear", "fuzzy fuzzy was a bear"));
+/// assert_eq!(100, token_set_ratio("New York Mets", "mets, new york"));
+/// ```
+pub fn token_set_ratio(a: &str, b: &str) -> u8 {
+    token_set_ratio_of_sets(&token_set(a), &token_set(b))
+}
+
+/// A set of candidates for repeated [`token_set_ratio`] queries. The token
+/// sets of the candidates are only calculated once, when the store is built.
+///
+/// ```
+/// use strsim::TokenStore;
+///
+/// let store = TokenStore::new(&["new york mets", "new york yankees"]);
+/// assert_eq!(
+///     vec![("new york mets", 100), ("new york yankees", 76)],
+///     store.query("Mets, New York")
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenStore<'a> {
+    candidates: Vec<(&'a str, BTreeSet<String>)>,
+}
+
+impl<'a> TokenStore<'a> {
+    /// Creates a store from the candidates and caches their token sets.
+    pub fn new(candidates: &[&'a str]) -> Self {
+        Self {
+            candidates: candidates
+                .iter()
+                .map(|&candidate| (candidate, token_set(candidate)))
+                .collect(),
+        }
+    }
+
+    /// Calculates the [`token_set_ratio`] of `query` with every candidate,
+    /// in the order the candidates were provided.
+    pub fn query(&self, query: &str) -> Vec<(&'a str, u8)> {
+        let query_tokens = token_set(query);
+        self.candidates
+            .iter()
+            .map(|(candidate, tokens)| (*candidate, token_set_ratio_of_sets(&query_tokens, tokens)))
+            .collect()
+    }
+}
+
+/// Like Levenshtein, but every `'*'` in `pattern` matches any run of zero or
+/// more characters of `text` for free, like in a glob pattern. All other
+/// characters have to be matched by insertions, deletions, and substitutions
+/// as usual.
+///
+/// ```
+/// use strsim::glob_levenshtein;
+///
+/// assert_eq!(0, glob_levenshtein("a*c", "abbbc"));
+/// assert_eq!(1, glob_levenshtein("a*c", "abbbd"));
+/// ```
+pub fn glob_levenshtein(pattern: &str, text: &str) -> usize {
+    let text_len = text.chars().count();
+    let mut prev: Vec<usize> = (0..text_len + 1).collect();
+    let mut curr = vec![0; text_len + 1];
+
+    for pattern_char in pattern.chars() {
+        curr[0] = if pattern_char == '*' {
+            prev[0]
+        } else {
+            prev[0] + 1
+        };
+
+        for (j, text_char) in text.chars().enumerate() {
+            curr[j + 1] = if pattern_char == '*' {
+                // the star either matches nothing or consumes one more char
+                min(prev[j + 1], curr[j])
+            } else {
+                let cost = usize::from(pattern_char != text_char);
+                min(prev[j] + cost, min(prev[j + 1] + 1, curr[j] + 1))
+            };
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[text_len]
+}
+
+/// Returns all pairs of `left` and `right` strings with a similarity of at
+/// least `threshold` according to `metric`, together with their score. The
+/// pairs are ordered like the `left` strings and then the `right` strings.
+///
+/// Every pair is scored. `metric` is an arbitrary function, so no n-gram
+/// count derived from `threshold` is guaranteed to hold for all matches, and
+/// prefiltering the pairs could lose some of them.
+///
+/// ```
+/// use strsim::{jaro_winkler, similarity_join};
+///
+/// let left = ["apple", "banana"];
+/// let right = ["appel", "bananas", "cherry"];
+/// let pairs = similarity_join(&left, &right, 0.9, jaro_winkler);
+///
+/// assert_eq!(2, pairs.len());
+/// assert_eq!(("apple", "appel"), (pairs[0].0, pairs[0].1));
+/// assert_eq!(("banana", "bananas"), (pairs[1].0, pairs[1].1));
+/// ```
+pub fn similarity_join<'a>(
+    left: &'a [&'a str],
+    right: &'a [&'a str],
+    threshold: f64,
+    metric: Scorer,
+) -> Vec<(&'a str, &'a str, f64)> {
+    let mut pairs = Vec::new();
+    for &l in left {
+        for &r in right {
+            let score = metric(l, r);
+            if score >= threshold {
+                pairs.push((l, r, score));
+            }
+        }
+    }
+    pairs
+}
+
+/// Like optimal string alignment, but two characters up to `max_swap_distance`
+/// positions apart can be swapped for a cost of 1, as long as the characters
+/// between them are unchanged. With a `max_swap_distance` of 1 this is
+/// identical to [`osa_distance`], a `max_swap_distance` of 0 disables
+/// transpositions.
+///
+/// ```
+/// use strsim::osa_window;
+///
+/// assert_eq!(2, osa_window("abc", "cba", 1));
+/// assert_eq!(1, osa_window("abc", "cba", 2));
+/// ```
+pub fn osa_window(a: &str, b: &str, max_swap_distance: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let width = b.len() + 1;
+    let mut distances = vec![0; (a.len() + 1) * width];
+    for i in 0..a.len() + 1 {
+        distances[i * width] = i;
+    }
+    for (j, distance) in distances.iter_mut().take(width).enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..a.len() + 1 {
+        for j in 1..b.len() + 1 {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = min(
+                distances[(i - 1) * width + j - 1] + cost,
+                min(
+                    distances[(i - 1) * width + j] + 1,
+                    distances[i * width + j - 1] + 1,
+                ),
+            );
+
+            if cost == 1 {
+                for swap in 1..min(max_swap_distance, min(i, j) - 1) + 1 {
+                    let (a_start, b_start) = (i - 1 - swap, j - 1 - swap);
+                    if a[i - 1] == b[b_start]
+                        && a[a_start] == b[j - 1]
+                        && a[a_start + 1..i - 1] == b[b_start + 1..j - 1]
+                    {
+                        distance = min(distance, distances[a_start * width + b_start] + 1);
+                    }
+                }
+            }
+
+            distances[i * width + j] = distance;
+        }
+    }
+
+    distances[a.len() * width + b.len()]
+}
+
+/// A string metric providing both its raw value and a normalized similarity
+/// between 0.0 and 1.0, where 1.0 means the strings are the same. This allows
+/// generic code to work with any metric.
+///
+/// ```
+/// use strsim::{Distance, Jaro, Levenshtein};
+///
+/// let metrics: [&dyn Distance; 2] = [&Levenshtein::new(), &Jaro::new()];
+/// assert_eq!(3.0, metrics[0].raw("kitten", "sitting"));
+/// assert_eq!(metrics[1].raw("kitten", "sitting"), metrics[1].normalized("kitten", "sitting"));
+/// ```
+pub trait Distance {
+    /// The raw value of the metric, e.g. the number of edits.
+    fn raw(&self, a: &str, b: &str) -> f64;
+
+    /// The similarity between 0.0 and 1.0 (inclusive), where 1.0 means the
+    /// strings are the same.
+    fn normalized(&self, a: &str, b: &str) -> f64;
+}
+
+/// The Levenshtein distance as a [`Distance`], optionally refusing inputs
+/// longer than a configured number of characters. Such a limit bounds the
+/// time spent on untrusted input, which grows with the product of the string
+/// lengths.
+///
+/// ```
+/// use strsim::{Levenshtein, StrSimError};
+///
+/// let limited = Levenshtein::new().max_len(8);
+/// assert_eq!(Ok(3), limited.try_distance("kitten", "sitting"));
+/// assert_eq!(
+///     Err(StrSimError::InputTooLong { len: 10, max_len: 8 }),
+///     limited.try_distance("kitten", "sittingbou")
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Levenshtein {
+    max_len: Option<usize>,
+}
+
+impl Levenshtein {
+    /// Creates a Levenshtein metric without a length limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of characters either input may have.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Returns an error if either string exceeds the maximum length.
+    fn check_len(&self, a: &str, b: &str) -> Result<(), StrSimError> {
+        if let Some(max_len) = self.max_len {
+            for s in [a, b].iter() {
+                let len = s.chars().count();
+                if len > max_len {
+                    return Err(StrSimError::InputTooLong { len, max_len });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Calculates the Levenshtein distance, or returns
+    /// [`StrSimError::InputTooLong`] if either string has more characters
+    /// than the configured maximum.
+    pub fn try_distance(&self, a: &str, b: &str) -> Result<usize, StrSimError> {
+        self.check_len(a, b)?;
+        Ok(levenshtein(a, b))
+    }
+}
+
+/// Inputs exceeding the maximum length are treated as infinitely far apart,
+/// i.e. their raw value is `f64::INFINITY` and their normalized value 0.0.
+impl Distance for Levenshtein {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        self.try_distance(a, b)
+            .map_or(f64::INFINITY, |distance| distance as f64)
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        match self.check_len(a, b) {
+            Ok(()) => normalized_levenshtein(a, b),
+            Err(_) => 0.0,
+        }
+    }
+}
+
+/// The Damerau-Levenshtein distance as a [`Distance`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DamerauLevenshtein;
+
+impl Distance for DamerauLevenshtein {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        damerau_levenshtein(a, b) as f64
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        normalized_damerau_levenshtein(a, b)
+    }
+}
+
+/// The Hamming distance as a [`Distance`]. Unlike [`hamming`] this accepts
+/// strings of different lengths, counting every character of the longer
+/// string without a counterpart as differing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hamming;
+
+impl Distance for Hamming {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        let a_len = a.chars().count();
+        let b_len = b.chars().count();
+        let equal = a.chars().zip(b.chars()).filter(|(x, y)| x == y).count();
+        (max(a_len, b_len) - equal) as f64
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        let max_len = max(a.chars().count(), b.chars().count());
+        if max_len == 0 {
+            return 1.0;
+        }
+        1.0 - self.raw(a, b) / max_len as f64
+    }
+}
+
+/// A configurable Jaro and Jaro-Winkler similarity. The default configuration
+/// reproduces [`jaro`] and [`jaro_winkler`], and the settings can be changed
+/// with the builder methods.
+///
+/// As a [`Distance`], it provides the Jaro similarity. Since that is already a
+/// similarity between 0.0 and 1.0, the raw and normalized values are the same.
+///
+/// ```
+/// use strsim::{jaro_winkler, Jaro};
+///
+/// assert_eq!(jaro_winkler("dixon", "dicksonx"), Jaro::new().winkler("dixon", "dicksonx"));
+///
+/// let jaro = Jaro::new().prefix_scale(0.2).max_prefix(2);
+/// assert!(jaro.winkler("dixon", "dicksonx") > jaro_winkler("dixon", "dicksonx"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jaro {
+    prefix_scale: f64,
+    max_prefix: usize,
+    boost_threshold: f64,
+    long_string_adjustment: bool,
+}
+
+impl Default for Jaro {
+    fn default() -> Self {
+        Self {
+            prefix_scale: 0.1,
+            max_prefix: 4,
+            boost_threshold: 0.7,
+            long_string_adjustment: false,
+        }
+    }
+}
+
+impl Jaro {
+    /// Creates the standard configuration, see [`Jaro::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how much every character of the common prefix boosts the
+    /// Jaro-Winkler similarity (0.1 by default). The product with
+    /// `max_prefix` should not exceed 1.0, or the similarity can exceed 1.0.
+    pub fn prefix_scale(mut self, prefix_scale: f64) -> Self {
+        self.prefix_scale = prefix_scale;
+        self
+    }
+
+    /// Sets the maximum length of the common prefix considered for the boost
+    /// (4 by default).
+    pub fn max_prefix(mut self, max_prefix: usize) -> Self {
+        self.max_prefix = max_prefix;
+        self
+    }
+
+    /// Sets the Jaro similarity a pair needs to exceed to receive the prefix
+    /// boost (0.7 by default).
+    pub fn boost_threshold(mut self, boost_threshold: f64) -> Self {
+        self.boost_threshold = boost_threshold;
+        self
+    }
+
+    /// Enables Winkler's adjustment for long strings (disabled by default).
+    /// If both strings are longer than 4 characters and they have more than
+    /// the common prefix plus one character in common (and at least half of
+    /// the shorter string beyond the prefix), the remaining distance to 1.0
+    /// is reduced in proportion to the additional matches.
+    pub fn long_string_adjustment(mut self, enabled: bool) -> Self {
+        self.long_string_adjustment = enabled;
+        self
+    }
+
+    /// Calculates the Jaro similarity, which is not affected by any of the
+    /// settings.
+    pub fn similarity(&self, a: &str, b: &str) -> f64 {
+        jaro(a, b)
+    }
+
+    /// Calculates the Jaro-Winkler similarity with the configured settings.
+    pub fn winkler(&self, a: &str, b: &str) -> f64 {
+        let (mut sim, matches) = jaro_impl(&StringWrapper(a), &StringWrapper(b), None);
+        if sim <= self.boost_threshold {
+            return sim;
+        }
+
+        let prefix_length = a
+            .chars()
+            .zip(b.chars())
+            .take(self.max_prefix)
+            .take_while(|(a_char, b_char)| a_char == b_char)
+            .count();
+        sim += self.prefix_scale * prefix_length as f64 * (1.0 - sim);
+
+        if self.long_string_adjustment {
+            let (a_len, b_len) = (a.chars().count(), b.chars().count());
+            let min_len = min(a_len, b_len);
+            if min_len > 4 && matches > prefix_length + 1 && 2 * matches >= min_len + prefix_length
+            {
+                sim += (1.0 - sim) * (matches - prefix_length - 1) as f64
+                    / (a_len + b_len - 2 * prefix_length + 2) as f64;
+            }
+        }
+
+        sim
+    }
+}
+
+impl Distance for Jaro {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        self.similarity(a, b)
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        self.similarity(a, b)
+    }
+}
+
+/// The longest common subsequence as a [`Distance`]. The raw value is the
+/// number of characters of both strings not part of the longest common
+/// subsequence (the Indel distance), the normalized value is the share of
+/// characters which are part of it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Lcs;
+
+impl Distance for Lcs {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        indel_distance(a, b) as f64
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        indel_ratio(a, b) / 100.0
+    }
+}
+
+/// The cached values of one pair of strings. `raw` and `normalized` are
+/// computed independently, so either may still be missing.
+#[derive(Debug, Clone, Copy, Default)]
+struct CachedScores {
+    raw: Option<f64>,
+    normalized: Option<f64>,
+}
+
+/// The cached values of one pair and the tick of its last use.
+#[derive(Debug)]
+struct LruEntry {
+    scores: CachedScores,
+    tick: u64,
+}
+
+#[derive(Debug, Default)]
+struct LruCache {
+    // keyed by `a` and then by `b`, so a hit doesn't have to allocate a key
+    entries: HashMap<String, HashMap<String, LruEntry>>,
+    // the pair last used at every tick, least recently used pair first
+    recency: BTreeMap<u64, (String, String)>,
+    next_tick: u64,
+}
+
+impl LruCache {
+    fn entry_mut(&mut self, a: &str, b: &str) -> Option<&mut LruEntry> {
+        self.entries
+            .get_mut(a)
+            .and_then(|entries| entries.get_mut(b))
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, (a, b))) = self.recency.pop_first() {
+            if let Some(entries) = self.entries.get_mut(&a) {
+                entries.remove(&b);
+                if entries.is_empty() {
+                    self.entries.remove(&a);
+                }
+            }
+        }
+    }
+}
+
+/// Wraps a [`Distance`] and memoizes its results for the `capacity` most
+/// recently used pairs of strings, which helps when the same pairs are
+/// compared over and over. Once the cache is full, the least recently used
+/// pair is evicted. Pairs are ordered, so `(a, b)` and `(b, a)` are cached
+/// separately.
+///
+/// A lookup takes time logarithmic in `capacity` and only allocates when a
+/// new pair is inserted.
+///
+/// ```
+/// use strsim::{Cached, Distance, Levenshtein};
+///
+/// let cached = Cached::new(Levenshtein::new(), 100);
+/// assert_eq!(3.0, cached.raw("kitten", "sitting"));
+/// assert_eq!(3.0, cached.raw("kitten", "sitting"));
+/// assert_eq!(1, cached.len());
+/// ```
+#[derive(Debug)]
+pub struct Cached<M> {
+    metric: M,
+    capacity: usize,
+    cache: RefCell<LruCache>,
+}
+
+impl<M: Distance> Cached<M> {
+    /// Wraps `metric` with a cache holding at most `capacity` pairs. A
+    /// capacity of 0 disables caching.
+    pub fn new(metric: M, capacity: usize) -> Self {
+        Cached {
+            metric,
+            capacity,
+            cache: RefCell::new(LruCache::default()),
+        }
+    }
+
+    /// Returns the wrapped metric.
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    /// Returns the maximum number of cached pairs.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of currently cached pairs.
+    pub fn len(&self) -> usize {
+        self.cache.borrow().recency.len()
+    }
+
+    /// Returns `true` if no pair is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached pairs.
+    pub fn clear(&self) {
+        let mut cache = self.cache.borrow_mut();
+        cache.entries.clear();
+        cache.recency.clear();
+    }
+
+    fn lookup<G, S>(
+        &self,
+        a: &str,
+        b: &str,
+        get: G,
+        set: S,
+        compute: fn(&M, &str, &str) -> f64,
+    ) -> f64
+    where
+        G: Fn(&CachedScores) -> Option<f64>,
+        S: Fn(&mut CachedScores, f64),
+    {
+        if self.capacity == 0 {
+            return compute(&self.metric, a, b);
+        }
+
+        let mut cache = self.cache.borrow_mut();
+        let tick = cache.next_tick;
+        cache.next_tick += 1;
+
+        if let Some(entry) = cache.entry_mut(a, b) {
+            let last_tick = mem::replace(&mut entry.tick, tick);
+            let cached = get(&entry.scores);
+            let key = cache.recency.remove(&last_tick).unwrap();
+            cache.recency.insert(tick, key);
+            if let Some(value) = cached {
+                return value;
+            }
+        } else {
+            if cache.recency.len() == self.capacity {
+                cache.evict_least_recently_used();
+            }
+            cache.entries.entry(a.to_string()).or_default().insert(
+                b.to_string(),
+                LruEntry {
+                    scores: CachedScores::default(),
+                    tick,
+                },
+            );
+            cache.recency.insert(tick, (a.to_string(), b.to_string()));
+        }
+
+        let value = compute(&self.metric, a, b);
+        set(&mut cache.entry_mut(a, b).unwrap().scores, value);
+        value
+    }
+}
+
+impl<M: Distance> Distance for Cached<M> {
+    fn raw(&self, a: &str, b: &str) -> f64 {
+        self.lookup(
+            a,
+            b,
+            |scores| scores.raw,
+            |scores, value| scores.raw = Some(value),
+            M::raw,
+        )
+    }
+
+    fn normalized(&self, a: &str, b: &str) -> f64 {
+        self.lookup(
+            a,
+            b,
+            |scores| scores.normalized,
+            |scores, value| scores.normalized = Some(value),
+            M::normalized,
+        )
+    }
+}
+
+/// Like Levenshtein, but the cost of every operation is determined by a
+/// closure: `insert_cost` for inserting a character of `b`, `delete_cost` for
+/// deleting a character of `a` and `sub_cost` for replacing a character of `a`
+/// with a different character of `b`. Keeping a character is always free.
+///
+/// ```
+/// use strsim::levenshtein_directional;
+///
+/// let is_vowel = |c: char| "aeiou".contains(c);
+/// let distance = levenshtein_directional(
+///     "smth",
+///     "smith",
+///     |c| if is_vowel(c) { 0.25 } else { 1.0 },
+///     |_| 1.0,
+///     |_, _| 1.0,
+/// );
+/// assert_eq!(0.25, distance);
+/// ```
+pub fn levenshtein_directional<I, D, S>(
+    a: &str,
+    b: &str,
+    insert_cost: I,
+    delete_cost: D,
+    sub_cost: S,
+) -> f64
+where
+    I: Fn(char) -> f64,
+    D: Fn(char) -> f64,
+    S: Fn(char, char) -> f64,
+{
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let costs = ClosureCosts {
+        insert: insert_cost,
+        delete: delete_cost,
+        substitute: sub_cost,
+    };
+    generic_weighted_levenshtein(&a, &b, &costs)
+}
+
+/// [`EditCosts`] of characters backed by closures, as used by
+/// [`levenshtein_directional`].
+struct ClosureCosts<I, D, S> {
+    insert: I,
+    delete: D,
+    substitute: S,
+}
+
+impl<I, D, S> EditCosts<char> for ClosureCosts<I, D, S>
+where
+    I: Fn(char) -> f64,
+    D: Fn(char) -> f64,
+    S: Fn(char, char) -> f64,
+{
+    fn insert(&self, elem: &char) -> f64 {
+        (self.insert)(*elem)
+    }
+
+    fn delete(&self, elem: &char) -> f64 {
+        (self.delete)(*elem)
+    }
+
+    fn substitute(&self, from: &char, to: &char) -> f64 {
+        (self.substitute)(*from, *to)
+    }
+}
+
+/// Finds the representative of the set containing `i` and compresses the path
+/// to it.
+fn union_find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Groups the indices of `items` into clusters of near duplicates. Two items
+/// end up in the same cluster if their similarity according to `metric` is at
+/// least `threshold`, or if they are transitively connected through other
+/// items. Every item is part of exactly one cluster, so unrelated items form
+/// clusters of their own.
+///
+/// The clusters are ordered by their smallest index and the indices within a
+/// cluster are sorted.
+///
+/// ```
+/// use strsim::{cluster_near_duplicates, normalized_levenshtein};
+///
+/// let items = ["color", "colour", "size", "colr"];
+/// assert_eq!(
+///     vec![vec![0, 1, 3], vec![2]],
+///     cluster_near_duplicates(&items, 0.75, normalized_levenshtein)
+/// );
+/// ```
+pub fn cluster_near_duplicates(items: &[&str], threshold: f64, metric: Scorer) -> Vec<Vec<usize>> {
+    let mut parents: Vec<usize> = (0..items.len()).collect();
+
+    for i in 0..items.len() {
+        for j in i + 1..items.len() {
+            if metric(items[i], items[j]) >= threshold {
+                let root_i = union_find_root(&mut parents, i);
+                let root_j = union_find_root(&mut parents, j);
+                parents[max(root_i, root_j)] = min(root_i, root_j);
+            }
+        }
+    }
+
+    // the root of every set is its smallest index, so clusters are created
+    // in order of their smallest index
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for i in 0..items.len() {
+        let root = union_find_root(&mut parents, i);
+        let cluster = *cluster_of_root.entry(root).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(i);
+    }
+
+    clusters
+}
+
+/// Maps every item to a canonical representative of its cluster of near
+/// duplicates, as built by [`cluster_near_duplicates`]. The representative is
+/// the most frequent spelling within the cluster, and the first one in
+/// `items` if several are equally frequent.
+///
+/// ```
+/// use strsim::{canonicalize, normalized_levenshtein};
+///
+/// let items = ["colour", "color", "color", "size"];
+/// let canonical = canonicalize(&items, 0.75, normalized_levenshtein);
+/// assert_eq!("color", canonical["colour"]);
+/// assert_eq!("size", canonical["size"]);
+/// ```
+pub fn canonicalize(items: &[&str], threshold: f64, metric: Scorer) -> HashMap<String, String> {
+    let mut canonical = HashMap::new();
+
+    for cluster in cluster_near_duplicates(items, threshold, metric) {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for &i in &cluster {
+            *counts.entry(items[i]).or_insert(0) += 1;
+        }
+
+        // `max_by_key` keeps the last maximum, so walk the cluster backwards
+        // to prefer the first spelling on ties
+        let representative = cluster
+            .iter()
+            .rev()
+            .map(|&i| items[i])
+            .max_by_key(|item| counts[item])
+            .unwrap();
+        for &i in &cluster {
+            canonical.insert(items[i].to_string(), representative.to_string());
+        }
+    }
+
+    canonical
+}
+
+/// Returns up to `max` candidates within a Levenshtein distance of
+/// `max_distance` to `query`, e.g. for "did you mean" hints in error messages.
+/// The suggestions are sorted by distance and then alphabetically, so the
+/// output is stable regardless of the order of `candidates`.
+///
+/// ```
+/// use strsim::suggestions;
+///
+/// let commands = ["status", "stash", "commit", "stage"];
+/// assert_eq!(vec!["stage", "stash"], suggestions("stahs", &commands, 2, 2));
+/// assert_eq!(vec!["stage"], suggestions("stahs", &commands, 1, 2));
+/// ```
+pub fn suggestions(
+    query: &str,
+    candidates: &[&str],
+    max: usize,
+    max_distance: usize,
+) -> Vec<String> {
+    let mut matches: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|&candidate| (levenshtein(query, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    matches.sort();
+
+    matches
+        .into_iter()
+        .take(max)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Calculates the number of differing bits among the first `bit_len` bits of
+/// the two buffers, e.g. for comparing perceptual hashes which are not a whole
+/// number of bytes long. Bits are counted from the most significant bit of the
+/// first byte, and any bits beyond `bit_len` are ignored.
+///
+/// Returns an error if the buffers have different lengths, or
+/// [`StrSimError::OutOfRange`] if `bit_len` exceeds the number of bits in
+/// the buffers.
+///
+/// ```
+/// use strsim::hamming_bits_n;
+///
+/// // the last bit is padding and differs, but is not compared
+/// assert_eq!(Ok(1), hamming_bits_n(&[0b1010_0000, 0b0000_0001], &[0b1000_0000, 0], 15));
+/// assert_eq!(Ok(2), hamming_bits_n(&[0b1010_0000, 0b0000_0001], &[0b1000_0000, 0], 16));
+/// ```
+pub fn hamming_bits_n(a: &[u8], b: &[u8], bit_len: usize) -> HammingResult {
+    if a.len() != b.len() {
+        return Err(StrSimError::DifferentLengthArgs {
+            len_a: a.len(),
+            len_b: b.len(),
+        });
+    }
+    if bit_len > a.len() * 8 {
+        return Err(StrSimError::OutOfRange {
+            requested: bit_len,
+            available: a.len() * 8,
+        });
+    }
+
+    let full_bytes = bit_len / 8;
+    let mut count: usize = a[..full_bytes]
+        .iter()
+        .zip(&b[..full_bytes])
+        .map(|(x, y)| (x ^ y).count_ones() as usize)
+        .sum();
+
+    let remaining_bits = bit_len % 8;
+    if remaining_bits > 0 {
+        let mask = !(0xffu8 >> remaining_bits);
+        count += ((a[full_bytes] ^ b[full_bytes]) & mask).count_ones() as usize;
+    }
+
+    Ok(count)
+}
+
+/// Like Levenshtein, but edits on some characters can be made more expensive
+/// than others, e.g. to penalize edits on rare characters. Inserting or
+/// deleting a character costs its weight in `char_weight`, and substituting
+/// one character for another costs the average of their weights. Characters
+/// missing from `char_weight` have a weight of 1.0.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use strsim::levenshtein_char_weighted;
+///
+/// let mut weights = HashMap::new();
+/// weights.insert('q', 2.0);
+///
+/// assert_eq!(1.0, levenshtein_char_weighted("cat", "bat", &weights));
+/// assert_eq!(1.5, levenshtein_char_weighted("cat", "qat", &weights));
+/// assert_eq!(2.0, levenshtein_char_weighted("qat", "at", &weights));
+/// ```
+pub fn levenshtein_char_weighted(a: &str, b: &str, char_weight: &HashMap<char, f64>) -> f64 {
+    let weight = |c: char| char_weight.get(&c).cloned().unwrap_or(1.0);
+    levenshtein_directional(a, b, weight, weight, |x, y| (weight(x) + weight(y)) / 2.0)
+}
+
+/// A pair of elements, either owned as the key of a substitution table or
+/// borrowed to look up such a key without cloning the elements.
+trait PairKey<T> {
+    fn pair(&self) -> (&T, &T);
+}
+
+impl<T> PairKey<T> for (T, T) {
+    fn pair(&self) -> (&T, &T) {
+        (&self.0, &self.1)
+    }
+}
+
+impl<'a, T> PairKey<T> for (&'a T, &'a T) {
+    fn pair(&self) -> (&T, &T) {
+        (self.0, self.1)
+    }
+}
+
+// hashes like the tuple `(T, T)`, which hashes its elements in order
+impl<'a, T: Hash> Hash for dyn PairKey<T> + 'a {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pair().hash(state);
+    }
+}
+
+impl<'a, T: Eq> PartialEq for dyn PairKey<T> + 'a {
+    fn eq(&self, other: &Self) -> bool {
+        self.pair() == other.pair()
+    }
+}
+
+impl<'a, T: Eq> Eq for dyn PairKey<T> + 'a {}
+
+impl<'a, T: 'a> Borrow<dyn PairKey<T> + 'a> for (T, T) {
+    fn borrow(&self) -> &(dyn PairKey<T> + 'a) {
+        self
+    }
+}
+
+/// Calculates the minimal cost of aligning two sequences, where inserting or
+/// deleting an element costs `gap` and replacing `x` with `y` costs
+/// `sub[&(x, y)]`. Equal elements without an entry in `sub` are kept for
+/// free, while different elements without an entry cannot be substituted and
+/// have to be deleted and inserted instead.
+///
+/// This generalizes weighted Levenshtein to arbitrary alphabets.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use strsim::alignment_cost;
+///
+/// let mut sub = HashMap::new();
+/// sub.insert((1, 2), 0.5);
+///
+/// assert_eq!(0.5, alignment_cost(&[1, 3], &[2, 3], 1.0, &sub));
+/// assert_eq!(2.0, alignment_cost(&[2, 3], &[1, 3], 1.0, &sub));
+/// ```
+pub fn alignment_cost<T: Eq + Hash>(a: &[T], b: &[T], gap: f64, sub: &HashMap<(T, T), f64>) -> f64 {
+    let mut prev: Vec<f64> = (0..=b.len()).map(|j| j as f64 * gap).collect();
+    let mut curr = vec![0.0; b.len() + 1];
+
+    for (i, a_elem) in a.iter().enumerate() {
+        curr[0] = (i + 1) as f64 * gap;
+
+        for (j, b_elem) in b.iter().enumerate() {
+            let key: &dyn PairKey<T> = &(a_elem, b_elem);
+            let substitution = match sub.get(key) {
+                Some(&cost) => prev[j] + cost,
+                None if a_elem == b_elem => prev[j],
+                None => f64::INFINITY,
+            };
+            let deletion = prev[j + 1] + gap;
+            let insertion = curr[j] + gap;
+            curr[j + 1] = substitution.min(deletion).min(insertion);
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Returns the indices of `candidates` sorted by descending similarity to
+/// `query` according to `metric`, so callers can reorder their own data
+/// without copying candidate references. Candidates with equal scores keep
+/// their original order.
+///
+/// ```
+/// use strsim::{jaro_winkler, rank_indices};
+///
+/// let candidates = ["apple", "banana", "appel"];
+/// assert_eq!(vec![0, 2, 1], rank_indices("apple", &candidates, jaro_winkler));
+/// ```
+pub fn rank_indices(query: &str, candidates: &[&str], metric: Scorer) -> Vec<usize> {
+    let scores: Vec<f64> = candidates.iter().map(|c| metric(query, c)).collect();
+    let mut indices: Vec<usize> = (0..candidates.len()).collect();
+    indices.sort_by(|&i, &j| scores[j].total_cmp(&scores[i]));
+    indices
+}
+
+/// Splits the string into grapheme clusters, each lowercased and in NFC.
+#[cfg(feature = "unicode")]
+fn folded_graphemes(s: &str) -> Vec<String> {
+    s.graphemes(true)
+        .map(|g| g.chars().flat_map(char::to_lowercase).nfc().collect())
+        .collect()
+}
+
+/// Like Levenshtein, but compares grapheme clusters instead of characters and
+/// ignores case. Every grapheme is lowercased and brought into NFC first, so
+/// precomposed and decomposed forms of the same letter are equal and an
+/// accented letter counts as a single edit.
+///
+/// ```
+/// use strsim::levenshtein_fold_graphemes;
+///
+/// assert_eq!(0, levenshtein_fold_graphemes("ZOË", "zoe\u{308}"));
+/// assert_eq!(1, levenshtein_fold_graphemes("Zoë", "zoe"));
+/// ```
+#[cfg(feature = "unicode")]
+pub fn levenshtein_fold_graphemes(a: &str, b: &str) -> usize {
+    generic_levenshtein(&folded_graphemes(a), &folded_graphemes(b))
+}
+
+/// Calculates a similarity between 0.0 and 1.0, choosing a metric suited to
+/// the inputs:
+///
+/// 1. If either string contains more than one word (separated by whitespace),
+///    [`token_set_ratio`] is used (scaled to 0.0 to 1.0), so word order and
+///    repeated words do not matter.
+/// 2. Otherwise, if both strings are at most 12 characters long,
+///    [`jaro_winkler`] is used, which works well for short words and names.
+/// 3. Otherwise [`normalized_levenshtein`] is used.
+///
+/// ```
+/// use strsim::smart_similarity;
+///
+/// assert_eq!(1.0, smart_similarity("New York Mets", "mets new york"));
+/// assert!(smart_similarity("martha", "marhta") > 0.95);
+/// ```
+pub fn smart_similarity(a: &str, b: &str) -> f64 {
+    const SHORT_STRING_LEN: usize = 12;
+
+    let is_multi_word = |s: &str| s.split_whitespace().nth(1).is_some();
+    if is_multi_word(a) || is_multi_word(b) {
+        f64::from(token_set_ratio(a, b)) / 100.0
+    } else if a.chars().count() <= SHORT_STRING_LEN && b.chars().count() <= SHORT_STRING_LEN {
+        jaro_winkler(a, b)
+    } else {
+        normalized_levenshtein(a, b)
+    }
+}
+
+/// Calculates [`normalized_levenshtein`] as a percentage between 0.0 and
+/// 100.0, rounded to `decimals` decimal places for display. Two empty
+/// strings are 100.0 percent similar.
+///
+/// ```
+/// use strsim::levenshtein_percent;
+///
+/// assert_eq!(57.1, levenshtein_percent("kitten", "sitting", 1));
+/// assert_eq!(57.14, levenshtein_percent("kitten", "sitting", 2));
+/// assert_eq!(100.0, levenshtein_percent("", "", 0));
+/// ```
+pub fn levenshtein_percent(a: &str, b: &str, decimals: u32) -> f64 {
+    let percent = normalized_levenshtein(a, b) * 100.0;
+    let factor = 10_f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX));
+    if factor.is_finite() {
+        (percent * factor).round() / factor
+    } else {
+        percent
+    }
+}
+
+/// Finds the longest common substring of `a[a_lo..a_hi]` and `b[b_lo..b_hi]`
+/// as `(i, j, len)`. Ties are resolved in favor of the earliest match in `a`,
+/// then in `b`.
+fn longest_match(
+    a: &[char],
+    b_positions: &HashMap<char, Vec<usize>>,
+    (a_lo, a_hi): (usize, usize),
+    (b_lo, b_hi): (usize, usize),
+) -> (usize, usize, usize) {
+    let mut best = (a_lo, b_lo, 0);
+    // length of the match ending at a[i - 1] and b[j], by j
+    let mut prev_lens: HashMap<usize, usize> = HashMap::new();
+
+    for (i, a_char) in a.iter().enumerate().take(a_hi).skip(a_lo) {
+        let mut lens = HashMap::new();
+        let positions = b_positions.get(a_char).map_or(&[][..], |p| &p[..]);
+        for &j in positions
+            .iter()
+            .skip_while(|&&j| j < b_lo)
+            .take_while(|&&j| j < b_hi)
+        {
+            let len = j
+                .checked_sub(1)
+                .and_then(|k| prev_lens.get(&k))
+                .map_or(1, |len| len + 1);
+            lens.insert(j, len);
+            if len > best.2 {
+                best = (i + 1 - len, j + 1 - len, len);
+            }
+        }
+        prev_lens = lens;
+    }
+
+    best
+}
+
+/// Returns the matching blocks of the Ratcliff/Obershelp ("gestalt pattern
+/// matching") decomposition as `(i, j, len)` triples, meaning that the `len`
+/// characters starting at character `i` of `a` equal those starting at
+/// character `j` of `b`. The longest common substring is matched first, and
+/// the parts to its left and right are decomposed recursively.
+///
+/// The blocks are sorted, adjacent blocks are merged and the list ends with a
+/// `(a_len, b_len, 0)` sentinel, like `get_matching_blocks` of Python's
+/// `difflib.SequenceMatcher` (without its junk heuristics, which only apply
+/// to sequences of 200 or more characters).
+///
+/// ```
+/// use strsim::matching_blocks;
+///
+/// assert_eq!(
+///     vec![(0, 0, 2), (3, 2, 2), (5, 4, 0)],
+///     matching_blocks("abxcd", "abcd")
+/// );
+/// ```
+pub fn matching_blocks(a: &str, b: &str) -> Vec<(usize, usize, usize)> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut b_positions: HashMap<char, Vec<usize>> = HashMap::new();
+    for (j, &b_char) in b.iter().enumerate() {
+        b_positions.entry(b_char).or_default().push(j);
+    }
+
+    let mut blocks = Vec::new();
+    let mut ranges = vec![((0, a.len()), (0, b.len()))];
+    while let Some(((a_lo, a_hi), (b_lo, b_hi))) = ranges.pop() {
+        let (i, j, len) = longest_match(&a, &b_positions, (a_lo, a_hi), (b_lo, b_hi));
+        if len == 0 {
+            continue;
+        }
+        blocks.push((i, j, len));
+        if a_lo < i && b_lo < j {
+            ranges.push(((a_lo, i), (b_lo, j)));
+        }
+        if i + len < a_hi && j + len < b_hi {
+            ranges.push(((i + len, a_hi), (j + len, b_hi)));
+        }
+    }
+    blocks.sort_unstable();
+
+    let mut merged: Vec<(usize, usize, usize)> = Vec::with_capacity(blocks.len() + 1);
+    for (i, j, len) in blocks {
+        match merged.last_mut() {
+            Some(last) if last.0 + last.2 == i && last.1 + last.2 == j => last.2 += len,
+            _ => merged.push((i, j, len)),
+        }
+    }
+    merged.push((a.len(), b.len(), 0));
+    merged
+}
+
+/// Returns the smallest distance between the two strings according to any of
+/// `metrics`, e.g. for conservative filtering with a lower bound of several
+/// distances. Returns `usize::MAX` if `metrics` is empty.
+///
+/// ```
+/// use strsim::{damerau_levenshtein, levenshtein, min_distance};
+///
+/// assert_eq!(1, min_distance("ab", "ba", &[levenshtein, damerau_levenshtein]));
+/// ```
+pub fn min_distance(a: &str, b: &str, metrics: &[fn(&str, &str) -> usize]) -> usize {
+    metrics
+        .iter()
+        .map(|metric| metric(a, b))
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+/// Solves the linear system `matrix * x = rhs` with Gauss-Jordan elimination.
+/// Variables without a usable pivot (in singular systems) are set to 0.0.
+fn solve_linear_system(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Vec<f64> {
+    let n = rhs.len();
+    let mut pivot_rows = vec![None; n];
+    let mut row = 0;
+
+    for col in 0..n {
+        let best = (row..n).max_by(|&x, &y| matrix[x][col].abs().total_cmp(&matrix[y][col].abs()));
+        let Some(best) = best else { break };
+        if matrix[best][col].abs() < 1e-12 {
+            continue;
+        }
+        matrix.swap(row, best);
+        rhs.swap(row, best);
+
+        let pivot_row = matrix[row].clone();
+        for other in 0..n {
+            if other != row {
+                let factor = matrix[other][col] / pivot_row[col];
+                for (value, pivot_value) in matrix[other].iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+                rhs[other] -= factor * rhs[row];
+            }
+        }
+        pivot_rows[col] = Some(row);
+        row += 1;
+    }
+
+    pivot_rows
+        .iter()
+        .enumerate()
+        .map(|(col, pivot_row)| pivot_row.map_or(0.0, |r| rhs[r] / matrix[r][col]))
+        .collect()
+}
+
+/// A weighted sum of several similarity metrics, whose weights can be fitted
+/// to labeled examples.
+///
+/// ```
+/// use strsim::{jaro, normalized_levenshtein, Ensemble};
+///
+/// let mut ensemble = Ensemble::new(vec![jaro, normalized_levenshtein]);
+/// ensemble.fit(&[
+///     ("martha".to_string(), "marhta".to_string(), 0.9),
+///     ("dixon".to_string(), "dicksonx".to_string(), 0.7),
+///     ("abc".to_string(), "xyz".to_string(), 0.0),
+/// ]);
+/// assert!(ensemble.score("martha", "martha") > 0.9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ensemble {
+    scorers: Vec<Scorer>,
+    weights: Vec<f64>,
+}
+
+impl Ensemble {
+    /// Creates an ensemble giving every scorer the same weight, so the score
+    /// is their average.
+    pub fn new(scorers: Vec<Scorer>) -> Self {
+        let weights = vec![1.0 / scorers.len() as f64; scorers.len()];
+        Self { scorers, weights }
+    }
+
+    /// Creates an ensemble from scorers and their weights.
+    pub fn with_weights(scorers: &[(Scorer, f64)]) -> Self {
+        Self {
+            scorers: scorers.iter().map(|&(scorer, _)| scorer).collect(),
+            weights: scorers.iter().map(|&(_, weight)| weight).collect(),
+        }
+    }
+
+    /// The current weights, in the order of the scorers.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Calculates the weighted sum of all scorers. Unlike [`blend`], the
+    /// weights are not normalized.
+    pub fn score(&self, a: &str, b: &str) -> f64 {
+        self.scorers
+            .iter()
+            .zip(&self.weights)
+            .map(|(scorer, weight)| scorer(a, b) * weight)
+            .sum()
+    }
+
+    /// Sets the weights to minimize the squared error between [`score`] and
+    /// the expected score of every `(a, b, expected)` pair. If the examples
+    /// don't determine all weights (e.g. because two scorers always agree),
+    /// the undetermined weights are set to 0.0.
+    ///
+    /// [`score`]: Ensemble::score
+    pub fn fit(&mut self, pairs: &[(String, String, f64)]) {
+        let n = self.scorers.len();
+        let mut normal_matrix = vec![vec![0.0; n]; n];
+        let mut normal_rhs = vec![0.0; n];
+
+        for (a, b, expected) in pairs {
+            let scores: Vec<f64> = self.scorers.iter().map(|scorer| scorer(a, b)).collect();
+            for i in 0..n {
+                for j in 0..n {
+                    normal_matrix[i][j] += scores[i] * scores[j];
+                }
+                normal_rhs[i] += scores[i] * expected;
+            }
+        }
+
+        self.weights = solve_linear_system(normal_matrix, normal_rhs);
+    }
+}
+
+/// Like Jaro-Winkler, but known abbreviations are expanded in both strings
+/// first, so e.g. "St. John" and "Saint John" still share a common prefix.
+/// Only whole words (separated by whitespace) are replaced, and the lookup is
+/// case-sensitive. Runs of whitespace are collapsed into a single space
+/// before comparing.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use strsim::jaro_winkler_abbrev;
+///
+/// let mut abbrevs = HashMap::new();
+/// abbrevs.insert("St.", "Saint");
+///
+/// assert_eq!(1.0, jaro_winkler_abbrev("St. John", "Saint John", &abbrevs));
+/// ```
+pub fn jaro_winkler_abbrev(a: &str, b: &str, abbrevs: &HashMap<&str, &str>) -> f64 {
+    let expand = |s: &str| {
+        s.split_whitespace()
+            .map(|word| abbrevs.get(word).cloned().unwrap_or(word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    jaro_winkler(&expand(a), &expand(b))
+}
+
+/// Like Levenshtein, but every run of the same character is collapsed into a
+/// single character first, so repetitions are ignored. This means that e.g.
+/// "aaa" and "a" are equal.
+///
+/// ```
+/// use strsim::rle_levenshtein;
+///
+/// assert_eq!(0, rle_levenshtein("cooool", "cool"));
+/// assert_eq!(1, rle_levenshtein("cooool", "coal"));
+/// ```
+pub fn rle_levenshtein(a: &str, b: &str) -> usize {
+    let collapse = |s: &str| {
+        let mut chars: Vec<char> = s.chars().collect();
+        chars.dedup();
+        chars
+    };
+    generic_levenshtein(&collapse(a), &collapse(b))
+}
+
+/// Calculates a similarity between 0.0 and 1.0 based on the best local
+/// alignment (Smith-Waterman) of the two strings, scoring +1 for a match and
+/// -1 for a mismatch or gap. The score is divided by the length of the shorter
+/// string, so a string embedded in another one with noise around it scores
+/// 1.0. Two empty strings are identical, but an empty string is not similar
+/// to a non-empty one.
+///
+/// ```
+/// use strsim::local_similarity;
+///
+/// assert_eq!(1.0, local_similarity("error in the matrix", "matrix"));
+/// assert_eq!(0.0, local_similarity("abc", "xyz"));
+/// ```
+pub fn local_similarity(a: &str, b: &str) -> f64 {
+    let b: Vec<char> = b.chars().collect();
+    let a_len = a.chars().count();
+    let shorter_len = min(a_len, b.len());
+    if shorter_len == 0 {
+        return if a_len == b.len() { 1.0 } else { 0.0 };
+    }
+
+    let mut prev = vec![0_i64; b.len() + 1];
+    let mut curr = vec![0_i64; b.len() + 1];
+    let mut best = 0;
+
+    for a_char in a.chars() {
+        for (j, &b_char) in b.iter().enumerate() {
+            let diagonal = prev[j] + if a_char == b_char { 1 } else { -1 };
+            let score = diagonal.max(prev[j + 1] - 1).max(curr[j] - 1).max(0);
+            curr[j + 1] = score;
+            best = best.max(score);
+        }
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    (best as f64 / shorter_len as f64).min(1.0)
+}
+
+/// Builds a full square matrix from the rows of its upper triangle, where row
+/// `i` holds the entries from column `i` onwards.
+fn mirror_upper_triangle<T: Clone>(upper: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let mut matrix: Vec<Vec<T>> = Vec::with_capacity(upper.len());
+    for (i, row) in upper.into_iter().enumerate() {
+        let mut full_row: Vec<T> = matrix.iter().map(|prev_row| prev_row[i].clone()).collect();
+        full_row.extend(row);
+        matrix.push(full_row);
+    }
+    matrix
+}
+
+/// Calculates the pairwise distances between all `items`, so that
+/// `matrix[i][j]` is `metric(items[i], items[j])`. The metric is assumed to be
+/// symmetric: it is only evaluated for `i <= j` and the result is mirrored.
+///
+/// ```
+/// use strsim::{distance_matrix, levenshtein};
+///
+/// let matrix = distance_matrix(&["kitten", "sitting", "mitten"], levenshtein);
+/// assert_eq!(vec![vec![0, 3, 1], vec![3, 0, 3], vec![1, 3, 0]], matrix);
+/// ```
+pub fn distance_matrix<T, F>(items: &[&str], metric: F) -> Vec<Vec<T>>
+where
+    T: Clone,
+    F: Fn(&str, &str) -> T,
+{
+    let upper = (0..items.len())
+        .map(|i| {
+            items[i..]
+                .iter()
+                .map(|item| metric(items[i], item))
+                .collect()
+        })
+        .collect();
+    mirror_upper_triangle(upper)
+}
+
+/// Like [`distance_matrix`], but the rows of the upper triangle are
+/// calculated in parallel. The result is identical to the sequential version.
+///
+/// ```
+/// use strsim::{distance_matrix, jaro, par_distance_matrix};
+///
+/// let items = ["martha", "marhta", "dixon", "dicksonx"];
+/// assert_eq!(distance_matrix(&items, jaro), par_distance_matrix(&items, jaro));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_distance_matrix<T, F>(items: &[&str], metric: F) -> Vec<Vec<T>>
+where
+    T: Clone + Send,
+    F: Fn(&str, &str) -> T + Sync,
+{
+    let upper = (0..items.len())
+        .into_par_iter()
+        .map(|i| {
+            items[i..]
+                .iter()
+                .map(|item| metric(items[i], item))
+                .collect()
+        })
+        .collect();
+    mirror_upper_triangle(upper)
+}
+
+/// Returns a table whose entry `[i][j]` is the length of the longest common
+/// subsequence of `a[i..]` and `b[j..]`.
+fn lcs_suffix_lens<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Vec<usize>> {
+    let mut suffix_lens = vec![vec![0_usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            suffix_lens[i][j] = if a[i] == b[j] {
+                suffix_lens[i + 1][j + 1] + 1
+            } else {
+                max(suffix_lens[i + 1][j], suffix_lens[i][j + 1])
+            };
+        }
+    }
+    suffix_lens
+}
+
+/// Returns a longest common subsequence of two slices. If there are several,
+/// elements earlier in `a` are preferred.
+///
+/// ```
+/// use strsim::lcs;
+///
+/// assert_eq!(vec![1, 3, 4], lcs(&[1, 2, 3, 4], &[1, 3, 5, 4]));
+/// ```
+pub fn lcs<T: PartialEq + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let suffix_lens = lcs_suffix_lens(a, b);
+    let mut subsequence = Vec::with_capacity(suffix_lens[0][0]);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            subsequence.push(a[i].clone());
+            i += 1;
+            j += 1;
+        } else if suffix_lens[i][j + 1] >= suffix_lens[i + 1][j] {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    subsequence
+}
+
+/// Returns a longest common subsequence of the characters of two strings,
+/// see [`lcs`].
+///
+/// ```
+/// use strsim::lcs_str;
+///
+/// assert_eq!("ace", lcs_str("abcde", "ace"));
+/// ```
+pub fn lcs_str(a: &str, b: &str) -> String {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    lcs(&a, &b).into_iter().collect()
+}
+
+/// Calculates how much of two slices is covered by their longest common
+/// subsequence, as `2 * lcs_len / (a.len() + b.len())`. The result is between
+/// 0.0 and 1.0 (inclusive) and rewards content shared in the same order. Two
+/// empty slices have a similarity of 1.0.
+///
+/// ```
+/// use strsim::lcs_similarity;
+///
+/// assert_eq!(0.75, lcs_similarity(&["a", "b", "c", "d"], &["a", "c", "d", "e"]));
+/// assert_eq!(1.0, lcs_similarity::<u8>(&[], &[]));
+/// ```
+pub fn lcs_similarity<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    let total_len = a.len() + b.len();
+    if total_len == 0 {
+        return 1.0;
+    }
+    (2 * lcs_len(a, b)) as f64 / total_len as f64
+}
+
+/// Calculates the [`lcs_similarity`] of the characters of two strings.
+///
+/// ```
+/// use strsim::lcs_str_similarity;
+///
+/// assert_eq!(0.75, lcs_str_similarity("abcd", "acde"));
+/// ```
+pub fn lcs_str_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    lcs_similarity(&a, &b)
+}
+
+/// How [`best_match`] and [`top_matches`] order candidates with equal scores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The candidate appearing first in the input wins.
+    #[default]
+    First,
+    /// The candidate appearing last in the input wins.
+    Last,
+    /// The candidate with the fewest characters wins. Candidates of the same
+    /// length are ordered like with `First`.
+    Shortest,
+    /// The lexicographically smallest candidate wins. Equal candidates are
+    /// ordered like with `First`.
+    Alphabetical,
+}
+
+/// Returns the `n` candidates most similar to `query` according to `metric`
+/// with their scores, ordered by descending score. Candidates with equal
+/// scores are ordered according to `tie_break`.
+///
+/// ```
+/// use strsim::{jaro_winkler, top_matches, TieBreak};
+///
+/// let candidates = ["apply", "apple", "banana"];
+/// let matches = top_matches("appl", &candidates, 2, jaro_winkler, TieBreak::Alphabetical);
+/// assert_eq!(vec!["apple", "apply"], matches.iter().map(|m| m.0).collect::<Vec<_>>());
+/// ```
+pub fn top_matches<'a>(
+    query: &str,
+    candidates: &[&'a str],
+    n: usize,
+    metric: Scorer,
+    tie_break: TieBreak,
+) -> Vec<(&'a str, f64)> {
+    let mut scored: Vec<(usize, &'a str, f64)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, &candidate)| (i, candidate, metric(query, candidate)))
+        .collect();
+
+    scored.sort_by(|&(i, a, a_score), &(j, b, b_score)| {
+        b_score.total_cmp(&a_score).then_with(|| match tie_break {
+            TieBreak::First => i.cmp(&j),
+            TieBreak::Last => j.cmp(&i),
+            TieBreak::Shortest => a.chars().count().cmp(&b.chars().count()).then(i.cmp(&j)),
+            TieBreak::Alphabetical => a.cmp(b).then(i.cmp(&j)),
+        })
+    });
+
+    scored
+        .into_iter()
+        .take(n)
+        .map(|(_, candidate, score)| (candidate, score))
+        .collect()
+}
+
+/// Returns the candidate most similar to `query` according to `metric` with
+/// its score, or `None` if there are no candidates. Ties are resolved
+/// according to `tie_break`.
+///
+/// ```
+/// use strsim::{best_match, normalized_levenshtein, TieBreak};
+///
+/// let candidates = ["cart", "cast", "cut"];
+/// assert_eq!(
+///     Some(("cart", 0.75)),
+///     best_match("cant", &candidates, normalized_levenshtein, TieBreak::First)
+/// );
+/// assert_eq!(
+///     Some(("cast", 0.75)),
+///     best_match("cant", &candidates, normalized_levenshtein, TieBreak::Last)
+/// );
+/// ```
+pub fn best_match<'a>(
+    query: &str,
+    candidates: &[&'a str],
+    metric: Scorer,
+    tie_break: TieBreak,
+) -> Option<(&'a str, f64)> {
+    top_matches(query, candidates, 1, metric, tie_break)
+        .into_iter()
+        .next()
+}
+
+/// Scores `query` against the members of every group and returns the name of
+/// the group, the member and the score of the best match, or `None` if no
+/// group has any members. Ties are resolved in favor of the member listed
+/// first.
+///
+/// ```
+/// use strsim::{grouped_best_match, normalized_levenshtein};
+///
+/// let groups = [
+///     ("fruit", vec!["apple", "banana"]),
+///     ("color", vec!["amber", "maple"]),
+/// ];
+/// assert_eq!(
+///     Some(("fruit", "apple", 0.8)),
+///     grouped_best_match("appl", &groups, normalized_levenshtein)
+/// );
+/// ```
+pub fn grouped_best_match<'a>(
+    query: &str,
+    groups: &'a [(&'a str, Vec<&'a str>)],
+    metric: Scorer,
+) -> Option<(&'a str, &'a str, f64)> {
+    groups
+        .iter()
+        .flat_map(|(group, members)| {
+            members
+                .iter()
+                .map(move |&member| (*group, member, metric(query, member)))
+        })
+        .fold(None, |best, current| match best {
+            Some(best) if best.2 >= current.2 => Some(best),
+            _ => Some(current),
+        })
+}
+
+/// Like [`damerau_levenshtein`], but the calculation stops as soon as the
+/// distance is known to exceed `limit`, in which case `limit + 1` is returned.
+/// This makes filtering a dictionary for close matches a lot faster.
+///
+/// ```
+/// use strsim::damerau_levenshtein_limit;
+///
+/// assert_eq!(2, damerau_levenshtein_limit("ab", "bca", 2));
+/// assert_eq!(2, damerau_levenshtein_limit("kitten", "sitting", 1));
+/// ```
+pub fn damerau_levenshtein_limit(a: &str, b: &str, limit: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let exceeded = limit.saturating_add(1);
+
+    if a.len().abs_diff(b.len()) > limit {
+        return exceeded;
+    }
+
+    let max_distance = a.len() + b.len();
+    // distances[i + 1][j + 1] is the distance between a[..i] and b[..j], with
+    // an extra row and column as sentinels for transpositions
+    let mut distances = vec![vec![0; b.len() + 2]; a.len() + 2];
+    distances[0] = vec![max_distance; b.len() + 2];
+    for (i, row) in distances.iter_mut().enumerate().skip(1) {
+        row[0] = max_distance;
+        row[1] = i - 1;
+    }
+    for j in 0..=b.len() {
+        distances[1][j + 1] = j;
+    }
+
+    let mut last_row: HashMap<char, usize> = HashMap::new();
+
+    for i in 1..=a.len() {
+        let mut last_col = 0;
+        let mut row_min = i;
+
+        for j in 1..=b.len() {
+            let k = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let l = last_col;
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            if cost == 0 {
+                last_col = j;
+            }
+
+            let distance = min(
+                min(distances[i][j] + cost, distances[i + 1][j] + 1),
+                min(
+                    distances[i][j + 1] + 1,
+                    distances[k][l] + (i - k - 1) + 1 + (j - l - 1),
+                ),
+            );
+            distances[i + 1][j + 1] = distance;
+            row_min = min(row_min, distance);
+        }
+
+        // the minimum of a row never decreases in later rows, so it is a
+        // lower bound of the final distance
+        if row_min > limit {
+            return exceeded;
+        }
+
+        last_row.insert(a[i - 1], i);
+    }
+
+    min(distances[a.len() + 1][b.len() + 1], exceeded)
+}
+
+/// The costs of the edit operations for [`weighted_levenshtein`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Costs {
+    /// The cost of inserting a character.
+    pub insert: f64,
+    /// The cost of deleting a character.
+    pub delete: f64,
+    /// The cost of replacing a character with a different one.
+    pub substitute: f64,
+}
+
+impl Default for Costs {
+    /// All operations cost 1.0, like in plain Levenshtein.
+    fn default() -> Self {
+        Self {
+            insert: 1.0,
+            delete: 1.0,
+            substitute: 1.0,
+        }
+    }
+}
+
+/// Like Levenshtein, but insertions, deletions, and substitutions have the
+/// costs given by `costs`.
+///
+/// ```
+/// use strsim::{weighted_levenshtein, Costs};
+///
+/// let costs = Costs { insert: 1.0, delete: 1.0, substitute: 3.0 };
+/// // replacing 'k' with 's' is more expensive than deleting and inserting
+/// assert_eq!(2.0, weighted_levenshtein("kat", "sat", &costs));
+/// ```
+pub fn weighted_levenshtein(a: &str, b: &str, costs: &Costs) -> f64 {
+    levenshtein_directional(
+        a,
+        b,
+        |_| costs.insert,
+        |_| costs.delete,
+        |_, _| costs.substitute,
+    )
+}
+
+/// Calculates a normalized score of [`weighted_levenshtein`] between 0.0 and
+/// 1.0 (inclusive), where 1.0 means the strings are the same.
+///
+/// Because the operations don't have unit costs, the distance is not divided
+/// by the number of characters, but by the cost of deleting all characters of
+/// `a` and inserting all characters of `b`. This is always a possible way to
+/// turn `a` into `b`, so the distance never exceeds it. Two empty strings
+/// (and any strings when all costs are 0.0) have a similarity of 1.0.
+///
+/// ```
+/// use strsim::{normalized_weighted_levenshtein, Costs};
+///
+/// let costs = Costs { insert: 2.0, delete: 1.0, substitute: 1.0 };
+/// // one substitution out of a maximum cost of 3 * 1.0 + 3 * 2.0
+/// let similarity = normalized_weighted_levenshtein("kat", "sat", &costs);
+/// assert!((similarity - (1.0 - 1.0 / 9.0)).abs() < 0.00001);
+/// ```
+pub fn normalized_weighted_levenshtein(a: &str, b: &str, costs: &Costs) -> f64 {
+    let max_cost =
+        a.chars().count() as f64 * costs.delete + b.chars().count() as f64 * costs.insert;
+    if max_cost == 0.0 {
+        return 1.0;
+    }
+    1.0 - weighted_levenshtein(a, b, costs) / max_cost
+}
+
+/// A cost model for [`generic_weighted_levenshtein`], defining what it costs
+/// to insert, delete or substitute elements of type `T`.
+///
+/// [`Costs`] implements it for every element type with constant costs.
+pub trait EditCosts<T> {
+    /// The cost of inserting `elem` into the first sequence.
+    fn insert(&self, elem: &T) -> f64;
+
+    /// The cost of deleting `elem` from the first sequence.
+    fn delete(&self, elem: &T) -> f64;
+
+    /// The cost of replacing `from` with `to`. Only called for elements
+    /// which are not equal, keeping an element is always free.
+    fn substitute(&self, from: &T, to: &T) -> f64;
+}
+
+impl<T> EditCosts<T> for Costs {
+    fn insert(&self, _: &T) -> f64 {
+        self.insert
+    }
+
+    fn delete(&self, _: &T) -> f64 {
+        self.delete
+    }
+
+    fn substitute(&self, _: &T, _: &T) -> f64 {
+        self.substitute
+    }
+}
+
+/// Like [`weighted_levenshtein`], but for slices of any element type, with
+/// the cost of every operation determined by `costs`. Equal elements are
+/// matched for free.
+///
+/// ```
+/// use strsim::{generic_weighted_levenshtein, EditCosts};
+///
+/// /// Replacing a number costs the difference of the values.
+/// struct Numeric;
+///
+/// impl EditCosts<i32> for Numeric {
+///     fn insert(&self, _: &i32) -> f64 {
+///         10.0
+///     }
+///
+///     fn delete(&self, _: &i32) -> f64 {
+///         10.0
+///     }
+///
+///     fn substitute(&self, from: &i32, to: &i32) -> f64 {
+///         f64::from((from - to).abs())
+///     }
+/// }
+///
+/// assert_eq!(3.0, generic_weighted_levenshtein(&[1, 5, 9], &[1, 7, 8], &Numeric));
+/// ```
+pub fn generic_weighted_levenshtein<T, C>(a: &[T], b: &[T], costs: &C) -> f64
+where
+    T: PartialEq,
+    C: EditCosts<T>,
+{
+    let mut prev = Vec::with_capacity(b.len() + 1);
+    prev.push(0.0);
+    for b_elem in b {
+        let last = prev[prev.len() - 1];
+        prev.push(last + costs.insert(b_elem));
+    }
+    let mut curr = vec![0.0; b.len() + 1];
+
+    for a_elem in a {
+        curr[0] = prev[0] + costs.delete(a_elem);
+
+        for (j, b_elem) in b.iter().enumerate() {
+            let substitution = if a_elem == b_elem {
+                prev[j]
+            } else {
+                prev[j] + costs.substitute(a_elem, b_elem)
+            };
+            let deletion = prev[j + 1] + costs.delete(a_elem);
+            let insertion = curr[j] + costs.insert(b_elem);
+            curr[j + 1] = substitution.min(deletion).min(insertion);
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Returns the Soundex digit of an ASCII letter, or `None` for vowels, `'h'`,
+/// `'w'`, `'y'` and all other characters.
+fn soundex_digit(c: char) -> Option<u8> {
+    match c.to_ascii_lowercase() {
+        'b' | 'f' | 'p' | 'v' => Some(1),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some(2),
+        'd' | 't' => Some(3),
+        'l' => Some(4),
+        'm' | 'n' => Some(5),
+        'r' => Some(6),
+        _ => None,
+    }
+}
+
+/// Like Levenshtein, but substituting a letter with another letter of the same
+/// Soundex class (e.g. `'c'` and `'k'`, or `'d'` and `'t'`) is free, so
+/// similar sounding spellings are closer. Letters are classified regardless of
+/// case. Vowels, `'h'`, `'w'`, `'y'` and non-letters have no class and are
+/// compared like in plain Levenshtein.
+///
+/// ```
+/// use strsim::{levenshtein, phonetic_levenshtein};
+///
+/// assert_eq!(1, levenshtein("Katherine", "Catherine"));
+/// assert_eq!(0, phonetic_levenshtein("Katherine", "Catherine"));
+/// ```
+pub fn phonetic_levenshtein(a: &str, b: &str) -> usize {
+    let substitution_cost = |x, y| match (soundex_digit(x), soundex_digit(y)) {
+        (Some(x_digit), Some(y_digit)) if x_digit == y_digit => 0.0,
+        _ => 1.0,
+    };
+    levenshtein_directional(a, b, |_| 1.0, |_| 1.0, substitution_cost) as usize
+}
+
+/// Calculates the distance between every pair of consecutive `items`, e.g. to
+/// detect drift in a list of versions. The result has one element less than
+/// `items`, and is empty if there are fewer than two items.
+///
+/// ```
+/// use strsim::{adjacent_distances, levenshtein};
+///
+/// assert_eq!(vec![1, 2], adjacent_distances(&["v1", "v2", "v2.1"], levenshtein));
+/// ```
+pub fn adjacent_distances(items: &[&str], metric: fn(&str, &str) -> usize) -> Vec<usize> {
+    items
+        .windows(2)
+        .map(|pair| metric(pair[0], pair[1]))
+        .collect()
+}
+
+/// Calculates the similarity of two sets of strings, such as tag lists, where
+/// elements only need to match approximately. Pairs of elements with an
+/// `inner` similarity of at least `threshold` are matched greedily, starting
+/// with the most similar pair, and every element is matched at most once. The
+/// result is the sum of the matched similarities divided by the size of the
+/// larger set. Two empty sets have a similarity of 1.0.
+///
+/// ```
+/// use strsim::{fuzzy_set_similarity, jaro_winkler};
+///
+/// let a = ["rust", "programming", "tutorial"];
+/// let b = ["programing", "rust"];
+/// let similarity = fuzzy_set_similarity(&a, &b, jaro_winkler, 0.9);
+/// assert!((similarity - (1.0 + jaro_winkler("programming", "programing")) / 3.0).abs() < 0.00001);
+/// ```
+pub fn fuzzy_set_similarity(a: &[&str], b: &[&str], inner: Scorer, threshold: f64) -> f64 {
+    let size = max(a.len(), b.len());
+    if size == 0 {
+        return 1.0;
+    }
+
+    let mut edges: Vec<(f64, usize, usize)> = Vec::new();
+    for (i, a_elem) in a.iter().enumerate() {
+        for (j, b_elem) in b.iter().enumerate() {
+            let score = inner(a_elem, b_elem);
+            if score >= threshold {
+                edges.push((score, i, j));
+            }
+        }
+    }
+    edges.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matched_weight = 0.0;
+    for (score, i, j) in edges {
+        if !a_matched[i] && !b_matched[j] {
+            a_matched[i] = true;
+            b_matched[j] = true;
+            matched_weight += score;
+        }
+    }
+
+    matched_weight / size as f64
+}
+
+/// The Hamming distance between a fixed pattern and a window sliding over a
+/// stream of bytes, e.g. to find approximate occurrences of the pattern. The
+/// window is kept internally, so the stream only has to be fed one byte at a
+/// time.
+///
+/// ```
+/// use strsim::SlidingHamming;
+///
+/// let stream = b"xxabcxabd";
+/// let mut sliding = SlidingHamming::new(b"abc", &stream[..3]).unwrap();
+/// assert_eq!(3, sliding.distance());
+/// assert_eq!(3, sliding.slide(stream[3]));
+/// assert_eq!(0, sliding.slide(stream[4]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlidingHamming {
+    pattern: Vec<u8>,
+    window: VecDeque<u8>,
+    distance: usize,
+}
+
+impl SlidingHamming {
+    /// Creates the sliding distance of `pattern` and the initial `window`.
+    /// Returns an error if they have different lengths.
+    pub fn new(pattern: &[u8], window: &[u8]) -> Result<Self, StrSimError> {
+        let distance = generic_hamming(pattern, window)?;
+        Ok(Self {
+            pattern: pattern.to_vec(),
+            window: window.iter().copied().collect(),
+            distance,
+        })
+    }
+
+    /// The Hamming distance between the pattern and the current window.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// Moves the window by one byte, dropping its oldest byte and appending
+    /// `in_byte`. Returns the updated distance.
+    ///
+    /// Moving the window shifts every byte of it under a different byte of
+    /// the pattern, so the distance is recomputed from scratch, which takes
+    /// time linear in the pattern length.
+    ///
+    /// For an empty pattern the window stays empty and the distance is 0.
+    pub fn slide(&mut self, in_byte: u8) -> usize {
+        if self.pattern.is_empty() {
+            return 0;
+        }
+
+        self.window.pop_front();
+        self.window.push_back(in_byte);
+
+        self.distance = self
+            .pattern
+            .iter()
+            .zip(&self.window)
+            .filter(|(x, y)| x != y)
+            .count();
+        self.distance
+    }
+}
+
+/// Advances the linear congruential generator `state` and returns 31 pseudo
+/// random bits.
+fn next_random(state: &mut u64) -> usize {
+    *state = state
+        .wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407);
+    (*state >> 33) as usize
+}
+
+/// Applies a random insertion, deletion or substitution of a lowercase ASCII
+/// letter to `chars`. Empty strings always receive an insertion.
+fn random_edit(state: &mut u64, chars: &mut Vec<char>) {
+    let letter = char::from(b'a' + (next_random(state) % 26) as u8);
+    let operation = if chars.is_empty() {
+        0
+    } else {
+        next_random(state) % 3
+    };
+
+    match operation {
+        0 => {
+            let pos = next_random(state) % (chars.len() + 1);
+            chars.insert(pos, letter);
+        }
+        1 => {
+            let pos = next_random(state) % chars.len();
+            chars.remove(pos);
+        }
+        _ => {
+            let pos = next_random(state) % chars.len();
+            chars[pos] = letter;
+        }
+    }
+}
+
+/// Estimates how quickly `metric` degrades under noise: `trials` times,
+/// `edits` random single character edits (insertions, deletions or
+/// substitutions of lowercase ASCII letters) are applied to `s`, and the
+/// average similarity of `s` to the edited strings is returned.
+///
+/// The edits are chosen by a pseudo random number generator seeded with
+/// `seed`, so the result is deterministic. Returns `metric(s, s)` if `trials`
+/// is 0.
+///
+/// ```
+/// use strsim::{noise_sensitivity, normalized_levenshtein};
+///
+/// let robustness = noise_sensitivity("similarity", 2, 100, normalized_levenshtein, 42);
+/// assert!(robustness >= 0.8 && robustness < 1.0);
+/// ```
+pub fn noise_sensitivity(s: &str, edits: usize, trials: usize, metric: Scorer, seed: u64) -> f64 {
+    if trials == 0 {
+        return metric(s, s);
+    }
+
+    let original: Vec<char> = s.chars().collect();
+    let mut state = seed;
+    let mut total = 0.0;
+    for _ in 0..trials {
+        let mut chars = original.clone();
+        for _ in 0..edits {
+            random_edit(&mut state, &mut chars);
+        }
+        let noisy: String = chars.into_iter().collect();
+        total += metric(s, &noisy);
+    }
+    total / trials as f64
+}
+
+/// The decision of [`classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchClass {
+    /// The score reached the accept threshold.
+    Match,
+    /// The score is between the reject and the accept threshold.
+    Uncertain,
+    /// The score is below the reject threshold.
+    NoMatch,
+}
+
+/// Turns the similarity of two strings into a discrete decision: `Match` if
+/// the score according to `metric` is at least `accept`, `NoMatch` if it is
+/// below `reject`, and `Uncertain` otherwise. If `reject` exceeds `accept`,
+/// scores of at least `accept` are still a `Match`.
+///
+/// ```
+/// use strsim::{classify, jaro_winkler, MatchClass};
+///
+/// assert_eq!(MatchClass::Match, classify("martha", "marhta", jaro_winkler, 0.9, 0.7));
+/// assert_eq!(MatchClass::Uncertain, classify("dixon", "dicksonx", jaro_winkler, 0.9, 0.7));
+/// assert_eq!(MatchClass::NoMatch, classify("abc", "xyz", jaro_winkler, 0.9, 0.7));
+/// ```
+pub fn classify(a: &str, b: &str, metric: Scorer, accept: f64, reject: f64) -> MatchClass {
+    let score = metric(a, b);
+    if score >= accept {
+        MatchClass::Match
+    } else if score < reject {
+        MatchClass::NoMatch
+    } else {
+        MatchClass::Uncertain
+    }
+}
+
+/// Calculates the similarity of two personal names between 0.0 and 1.0,
+/// combining a character level and a token level signal. Both names are
+/// lowercased, stripped of ASCII punctuation and whitespace is collapsed.
+/// The result is the average of [`jaro_winkler`], which tolerates typos, and
+/// [`token_set_ratio`] (scaled to 0.0 to 1.0), which tolerates reordered or
+/// additional name parts.
+///
+/// ```
+/// use strsim::name_similarity;
+///
+/// assert_eq!(1.0, name_similarity("Mary O'Brien", "MARY  OBRIEN"));
+/// assert!(name_similarity("O'Brien, Mary", "Mary O'Brien") > 0.75);
+/// assert!(name_similarity("Jon Smith", "John Smith") > 0.9);
+/// ```
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let clean = |s: &str| {
+        normalize::collapse_whitespace(&normalize::strip_punctuation(&normalize::fold_case(s)))
+    };
+    let (a, b) = (clean(a), clean(b));
+
+    let token_similarity = f64::from(token_set_ratio(&a, &b)) / 100.0;
+    (jaro_winkler(&a, &b) + token_similarity) / 2.0
+}
+
+/// Returns the name and score of the metric giving the highest similarity for
+/// the two strings, which helps to understand how metrics behave on some
+/// data. Ties are resolved in favor of the metric listed first. Returns
+/// `None` if there are no metrics.
+///
+/// ```
+/// use strsim::{argmax_metric, jaro, normalized_levenshtein, Scorer};
+///
+/// let metrics: [(&str, Scorer); 2] = [("levenshtein", normalized_levenshtein), ("jaro", jaro)];
+/// assert_eq!(Some("jaro"), argmax_metric("martha", "marhta", &metrics).map(|m| m.0));
+/// ```
+pub fn argmax_metric<'m>(
+    a: &str,
+    b: &str,
+    metrics: &[(&'m str, Scorer)],
+) -> Option<(&'m str, f64)> {
+    metrics
+        .iter()
+        .map(|&(name, metric)| (name, metric(a, b)))
+        .fold(None, |best, current| match best {
+            Some(best) if best.1 >= current.1 => Some(best),
+            _ => Some(current),
+        })
+}
+
+/// Like Levenshtein, but edits near the start of the strings cost more than
+/// edits near the end, e.g. for identifiers whose beginning is most
+/// significant. Deleting or substituting the character at position `i` of `a`
+/// costs `decay.powi(i)`, and inserting the character at position `j` of `b`
+/// costs `decay.powi(j)`, so with a `decay` below 1.0 the first character
+/// costs 1.0 and later ones less. A `decay` of 1.0 gives plain Levenshtein.
+///
+/// The result is not normalized. It is at most the cost of deleting all of
+/// `a` and inserting all of `b`.
+///
+/// ```
+/// use strsim::levenshtein_prefix_weighted;
+///
+/// assert_eq!(1.0, levenshtein_prefix_weighted("cat", "bat", 0.5));
+/// assert_eq!(0.25, levenshtein_prefix_weighted("cat", "cab", 0.5));
+/// ```
+pub fn levenshtein_prefix_weighted(a: &str, b: &str, decay: f64) -> f64 {
+    let b: Vec<char> = b.chars().collect();
+    let b_costs: Vec<f64> = (0..b.len()).map(|j| decay.powi(j as i32)).collect();
+
+    let mut prev = Vec::with_capacity(b.len() + 1);
+    prev.push(0.0);
+    for &cost in &b_costs {
+        let last = prev[prev.len() - 1];
+        prev.push(last + cost);
+    }
+    let mut curr = vec![0.0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        let a_cost = decay.powi(i as i32);
+        curr[0] = prev[0] + a_cost;
+
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = if a_char == b_char {
+                prev[j]
+            } else {
+                prev[j] + a_cost
+            };
+            let deletion = prev[j + 1] + a_cost;
+            let insertion = curr[j] + b_costs[j];
+            curr[j + 1] = substitution.min(deletion).min(insertion);
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// A summary of how two strings compare, see [`report`]. The `Display`
+/// implementation prints one value per line, e.g. for inspection tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityReport {
+    /// The number of characters of the first string.
+    pub len_a: usize,
+    /// The number of characters of the second string.
+    pub len_b: usize,
+    /// The distances and similarities of all basic metrics.
+    pub scores: Scores,
+    /// A longest common subsequence of the two strings.
+    pub lcs: String,
+    /// The number of shared character bigrams, see [`shared_ngrams`].
+    pub shared_bigrams: usize,
+}
+
+impl Display for SimilarityReport {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        let scores = &self.scores;
+        writeln!(fmt, "lengths: {} and {}", self.len_a, self.len_b)?;
+        match scores.hamming {
+            Some(hamming) => writeln!(fmt, "hamming: {hamming}")?,
+            None => writeln!(fmt, "hamming: -")?,
+        }
+        writeln!(
+            fmt,
+            "levenshtein: {} ({:.3})",
+            scores.levenshtein, scores.normalized_levenshtein
+        )?;
+        writeln!(fmt, "osa: {}", scores.osa_distance)?;
+        writeln!(
+            fmt,
+            "damerau-levenshtein: {} ({:.3})",
+            scores.damerau_levenshtein, scores.normalized_damerau_levenshtein
+        )?;
+        writeln!(fmt, "jaro: {:.3}", scores.jaro)?;
+        writeln!(fmt, "jaro-winkler: {:.3}", scores.jaro_winkler)?;
+        writeln!(fmt, "sorensen-dice: {:.3}", scores.sorensen_dice)?;
+        writeln!(fmt, "lcs: {:?}", self.lcs)?;
+        write!(fmt, "shared bigrams: {}", self.shared_bigrams)
+    }
+}
+
+/// Compares the two strings in every common way at once and bundles the
+/// results into a [`SimilarityReport`].
+///
+/// ```
+/// use strsim::report;
+///
+/// let report = report("kitten", "sitting");
+/// assert_eq!(3, report.scores.levenshtein);
+/// assert_eq!("ittn", report.lcs);
+/// println!("{report}");
+/// ```
+pub fn report(a: &str, b: &str) -> SimilarityReport {
+    SimilarityReport {
+        len_a: a.chars().count(),
+        len_b: b.chars().count(),
+        scores: all_scores(a, b),
+        lcs: lcs_str(a, b),
+        shared_bigrams: shared_ngrams(a, b, 2).expect("2 is a valid n-gram size"),
+    }
+}
+
+/// Like Levenshtein, but every match of `mask` is replaced with `placeholder`
+/// in both strings first, so volatile parts like timestamps or ids don't
+/// count as differences. The placeholder is inserted literally (`$` has no
+/// special meaning).
+///
+/// ```
+/// extern crate regex;
+/// extern crate strsim;
+///
+/// use regex::Regex;
+/// use strsim::levenshtein_masking;
+///
+/// let ids = Regex::new(r"#\d+").unwrap();
+/// assert_eq!(0, levenshtein_masking("closed #12", "closed #3456", &ids, "#N"));
+/// ```
+#[cfg(feature = "regex")]
+pub fn levenshtein_masking(a: &str, b: &str, mask: &Regex, placeholder: &str) -> usize {
+    levenshtein(
+        &mask.replace_all(a, NoExpand(placeholder)),
+        &mask.replace_all(b, NoExpand(placeholder)),
+    )
+}
+
+/// Like [`hamming`], but for strings which are already decoded into
+/// characters, which avoids decoding UTF-8 on every comparison. Returns an
+/// error if the slices have different lengths.
+///
+/// ```
+/// use strsim::hamming_chars;
+///
+/// let a: Vec<char> = "hamming".chars().collect();
+/// let b: Vec<char> = "hammers".chars().collect();
+/// assert_eq!(Ok(3), hamming_chars(&a, &b));
+/// ```
+pub fn hamming_chars(a: &[char], b: &[char]) -> HammingResult {
+    generic_hamming(a, b)
+}
+
+/// Returns the number of characters the two strings have in common at the
+/// start and at the end, as `(prefix, suffix)`. The suffix is only searched
+/// after the common prefix, so no character is counted twice: if one string
+/// is a prefix of the other, the suffix is 0.
+///
+/// ```
+/// use strsim::common_affixes;
+///
+/// assert_eq!((8, 4), common_affixes("prefix_match", "prefix_mismatch"));
+/// assert_eq!((3, 2), common_affixes("kitten", "kitchen"));
+/// ```
+pub fn common_affixes(a: &str, b: &str) -> (usize, usize) {
+    let prefix_bytes: usize = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    let (a_rest, b_rest) = (&a[prefix_bytes..], &b[prefix_bytes..]);
+
+    let prefix = a[..prefix_bytes].chars().count();
+    let suffix = a_rest
+        .chars()
+        .rev()
+        .zip(b_rest.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    (prefix, suffix)
+}
+
+/// Jaro-Winkler similarity that rewards a shared suffix as well as a
+/// shared prefix, which suits filenames with a common extension.
+///
+/// With `sim` the Jaro similarity, `l` the common prefix and `m` the common
+/// suffix (each capped at 4 characters, and never overlapping), the result
+/// when `sim > 0.7` is
+///
+/// ```text
+/// p = sim + prefix_scale * l * (1 - sim)
+/// p + suffix_scale * m * (1 - p)
+/// ```
+///
+/// Each step only closes part of the remaining gap to 1.0 when its scale
+/// is at most 0.25, and the result is clamped to 1.0 for larger scales.
+///
+/// ```
+/// use strsim::{jaro_winkler, jaro_winkler_suffix};
+///
+/// let a = "report_2023.csv";
+/// let b = "report_2024.csv";
+/// assert!(jaro_winkler_suffix(a, b, 0.1, 0.1) > jaro_winkler(a, b));
+/// ```
+pub fn jaro_winkler_suffix(a: &str, b: &str, prefix_scale: f64, suffix_scale: f64) -> f64 {
+    let sim = jaro(a, b);
+    if sim <= 0.7 {
+        return sim;
+    }
+
+    let (prefix, suffix) = common_affixes(a, b);
+    let with_prefix = sim + prefix_scale * prefix.min(4) as f64 * (1.0 - sim);
+    let boosted = with_prefix + suffix_scale * suffix.min(4) as f64 * (1.0 - with_prefix);
+    boosted.min(1.0)
+}
+
+/// Returns `s` together with every string obtained by deleting one character
+/// from it.
+fn single_deletions(s: &str) -> Vec<String> {
+    let mut variants = vec![s.to_string()];
+    for (i, c) in s.char_indices() {
+        let mut variant = String::with_capacity(s.len() - c.len_utf8());
+        variant.push_str(&s[..i]);
+        variant.push_str(&s[i + c.len_utf8()..]);
+        variants.push(variant);
+    }
+    variants
+}
+
+/// Dictionary index answering "which words are within one edit of this
+/// one" without comparing against every word, using the deletion
+/// neighbourhoods of the SymSpell algorithm.
+///
+/// Every dictionary word is stored under itself and all strings obtained by
+/// deleting one of its characters. Two words within Levenshtein distance 1
+/// always share such a variant, so a lookup only has to generate the
+/// variants of the query, which takes time proportional to its length
+/// rather than to the size of the dictionary. Candidates sharing a variant
+/// can still be two edits apart (e.g. a transposition), so they are checked
+/// with [`is_one_edit_away`] before being returned.
+///
+/// ```
+/// use strsim::DeletionIndex;
+///
+/// let index = DeletionIndex::new(&["hello", "help", "world", "word"]);
+/// assert_eq!(vec!["hello"], index.lookup("hallo"));
+/// assert_eq!(vec!["world", "word"], index.lookup("word"));
+/// assert!(index.lookup("xyz").is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeletionIndex {
+    words: Vec<String>,
+    variants: HashMap<String, Vec<usize>>,
+}
+
+impl DeletionIndex {
+    /// Builds the index for `dictionary`. Duplicate words are stored once.
+    pub fn new(dictionary: &[&str]) -> Self {
+        let mut index = Self::default();
+        let mut seen = HashSet::new();
+        for &word in dictionary {
+            if !seen.insert(word) {
+                continue;
+            }
+            let id = index.words.len();
+            index.words.push(word.to_string());
+            for variant in single_deletions(word) {
+                let ids = index.variants.entry(variant).or_default();
+                // a word like "aa" yields the same deletion more than once
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        index
+    }
+
+    /// Returns all dictionary words within Levenshtein distance 1 of `word`,
+    /// including `word` itself if it is in the dictionary, in dictionary
+    /// order.
+    pub fn lookup(&self, word: &str) -> Vec<&str> {
+        let mut ids: Vec<usize> = single_deletions(word)
+            .iter()
+            .filter_map(|variant| self.variants.get(variant))
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids.into_iter()
+            .map(|id| self.words[id].as_str())
+            .filter(|candidate| is_one_edit_away(word, candidate))
+            .collect()
+    }
+
+    /// Returns the number of distinct words in the index.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the index contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Token of [`numeric_aware_levenshtein`]: a single non-digit character or a
+/// whole run of ASCII digits with its leading zeros removed.
+#[derive(Debug, PartialEq, Eq)]
+enum NumericToken<'a> {
+    Char(char),
+    Number(&'a str),
+}
+
+fn numeric_tokens(s: &str) -> Vec<NumericToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let digits = rest[..end].trim_start_matches('0');
+            tokens.push(NumericToken::Number(if digits.is_empty() {
+                "0"
+            } else {
+                digits
+            }));
+            rest = &rest[end..];
+        } else {
+            tokens.push(NumericToken::Char(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    tokens
+}
+
+/// Levenshtein distance that treats every run of ASCII digits as a single
+/// token compared by numeric value, e.g. for version strings or numbered
+/// file names. `"007"` and `"7"` are equal, while replacing a number by a
+/// different one costs a single substitution regardless of how many digits
+/// either has. All other characters are compared one by one.
+///
+/// ```
+/// use strsim::numeric_aware_levenshtein;
+///
+/// assert_eq!(0, numeric_aware_levenshtein("file007", "file7"));
+/// assert_eq!(1, numeric_aware_levenshtein("v1.10", "v1.2"));
+/// assert_eq!(2, numeric_aware_levenshtein("v1.10", "v1"));
+/// ```
+pub fn numeric_aware_levenshtein(a: &str, b: &str) -> usize {
+    generic_levenshtein(&numeric_tokens(a), &numeric_tokens(b))
+}
+
+/// Options controlling how [`word_jaccard`] and [`word_dice`] turn strings
+/// into sets of words. By default words are compared exactly and nothing is
+/// dropped.
+///
+/// ```
+/// use strsim::{word_jaccard, WordSetOptions};
+///
+/// let options = WordSetOptions::new().lowercase(true).stopwords(&["the", "a"]);
+/// assert_eq!(1.0, word_jaccard("The Cat", "a cat", &options));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WordSetOptions {
+    lowercase: bool,
+    #[cfg(feature = "unicode")]
+    strip_accents: bool,
+    stopwords: Vec<String>,
+    // normalized once whenever the options change, not on every comparison
+    normalized_stopwords: HashSet<String>,
+}
+
+impl WordSetOptions {
+    /// Creates options that compare words exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether words are lowercased before being compared.
+    pub fn lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self.normalize_stopwords()
+    }
+
+    /// Sets whether diacritics are removed from words before they are
+    /// compared, see [`normalize::strip_diacritics`].
+    #[cfg(feature = "unicode")]
+    pub fn strip_accents(mut self, strip_accents: bool) -> Self {
+        self.strip_accents = strip_accents;
+        self.normalize_stopwords()
+    }
+
+    /// Sets the words which are ignored. The stopwords are normalized like
+    /// the words of the compared strings, so with lowercasing enabled `"the"`
+    /// also removes `"The"`.
+    pub fn stopwords(mut self, stopwords: &[&str]) -> Self {
+        self.stopwords = stopwords.iter().map(|word| word.to_string()).collect();
+        self.normalize_stopwords()
+    }
+
+    fn normalize_stopwords(mut self) -> Self {
+        self.normalized_stopwords = self
+            .stopwords
+            .iter()
+            .map(|word| self.normalize_word(word))
+            .collect();
+        self
+    }
+
+    fn normalize_word(&self, word: &str) -> String {
+        let word = if self.lowercase {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        };
+        #[cfg(feature = "unicode")]
+        let word = if self.strip_accents {
+            normalize::strip_diacritics(&word)
+        } else {
+            word
+        };
+        word
+    }
+
+    /// Splits `s` into its set of normalized alphanumeric words without the
+    /// stopwords.
+    fn word_set(&self, s: &str) -> HashSet<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| self.normalize_word(word))
+            .filter(|word| !self.normalized_stopwords.contains(word))
+            .collect()
+    }
+}
+
+/// Calculates the Jaccard similarity of the sets of words of the two strings,
+/// i.e. the number of shared words divided by the number of distinct words in
+/// either string. Words are the alphanumeric runs of the strings, normalized
+/// according to `options`. Returns 1.0 if neither string has any words.
+///
+/// ```
+/// use strsim::{word_jaccard, WordSetOptions};
+///
+/// let options = WordSetOptions::new();
+/// assert_eq!(0.5, word_jaccard("new york", "new york city mets", &options));
+/// ```
+pub fn word_jaccard(a: &str, b: &str, options: &WordSetOptions) -> f64 {
+    set_jaccard(&options.word_set(a), &options.word_set(b), true)
+}
+
+/// Calculates the Sørensen-Dice coefficient of the sets of words of the two
+/// strings, i.e. twice the number of shared words divided by the sum of the
+/// numbers of words in each string. Words are determined like in
+/// [`word_jaccard`]. Returns 1.0 if neither string has any words.
+///
+/// ```
+/// use strsim::{word_dice, WordSetOptions};
+///
+/// let options = WordSetOptions::new();
+/// assert_eq!(2.0 / 3.0, word_dice("new york", "new york city mets", &options));
+/// ```
+pub fn word_dice(a: &str, b: &str, options: &WordSetOptions) -> f64 {
+    let a = options.word_set(a);
+    let b = options.word_set(b);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// A keyboard layout for [`keyboard_levenshtein`], describing which keys are
+/// physically next to each other. The layout is given as rows of keys from
+/// top to bottom, where each row is assumed to be shifted by half a key to
+/// the right of the row above, as on common staggered keyboards. A key thus
+/// touches its left and right neighbours, the two keys above it at the same
+/// and the next column, and the two keys below it at the previous and the
+/// same column. Keys are matched case-insensitively.
+///
+/// ```
+/// use strsim::Layout;
+///
+/// let qwerty = Layout::qwerty();
+/// assert!(qwerty.are_adjacent('g', 'T'));
+/// assert!(!qwerty.are_adjacent('g', 'z'));
+///
+/// let qwertz = Layout::from_rows(&["qwertzuiop", "asdfghjkl", "yxcvbnm"]);
+/// assert!(qwertz.are_adjacent('t', 'z'));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    positions: HashMap<char, (usize, usize)>,
+    adjacent_cost: f64,
+}
+
+impl Layout {
+    /// Creates a layout from its rows of keys, ordered from top to bottom.
+    /// Adjacent keys cost 0.5 to substitute.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let mut positions = HashMap::new();
+        for (row, keys) in rows.iter().enumerate() {
+            for (column, key) in keys.chars().enumerate() {
+                for lower in key.to_lowercase() {
+                    positions.insert(lower, (row, column));
+                }
+            }
+        }
+        Layout {
+            positions,
+            adjacent_cost: 0.5,
+        }
+    }
+
+    /// The letter keys of a US QWERTY keyboard.
+    pub fn qwerty() -> Self {
+        Self::from_rows(&["qwertyuiop", "asdfghjkl", "zxcvbnm"])
+    }
+
+    /// Sets the cost of substituting a key with an adjacent one, 0.5 by
+    /// default. All other substitutions cost 1.0.
+    pub fn adjacent_cost(mut self, adjacent_cost: f64) -> Self {
+        self.adjacent_cost = adjacent_cost;
+        self
+    }
+
+    fn position(&self, key: char) -> Option<(usize, usize)> {
+        key.to_lowercase()
+            .next()
+            .and_then(|lower| self.positions.get(&lower))
+            .copied()
+    }
+
+    /// Returns `true` if the two keys are next to each other. Keys which are
+    /// not part of the layout are not adjacent to anything.
+    pub fn are_adjacent(&self, a: char, b: char) -> bool {
+        let ((row_a, col_a), (row_b, col_b)) = match (self.position(a), self.position(b)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+        if row_a == row_b {
+            col_a.abs_diff(col_b) == 1
+        } else if row_a + 1 == row_b {
+            // b is in the row below, which is shifted to the right
+            col_b == col_a || col_b + 1 == col_a
+        } else if row_b + 1 == row_a {
+            col_a == col_b || col_a + 1 == col_b
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+/// Like Levenshtein, but substituting a character with one on an adjacent
+/// key of `layout` is cheaper than other substitutions, which makes typical
+/// typos rank closer. Insertions and deletions cost 1.0. Characters are
+/// compared exactly, so only their cost depends on the case-insensitive key
+/// positions.
+///
+/// ```
+/// use strsim::{keyboard_levenshtein, Layout};
+///
+/// let layout = Layout::qwerty();
+/// assert_eq!(0.5, keyboard_levenshtein("tge", "the", &layout));
+/// assert_eq!(1.0, keyboard_levenshtein("tze", "the", &layout));
+/// ```
+pub fn keyboard_levenshtein(a: &str, b: &str, layout: &Layout) -> f64 {
+    levenshtein_directional(
+        a,
+        b,
+        |_| 1.0,
+        |_| 1.0,
+        |x, y| {
+            if layout.are_adjacent(x, y) {
+                layout.adjacent_cost
+            } else {
+                1.0
+            }
+        },
+    )
+}
+
+/// Scores `query` against every candidate and returns the scores sorted in
+/// ascending order, e.g. to pick a threshold relative to the typical scores
+/// with [`percentile`].
+///
+/// ```
+/// use strsim::{normalized_levenshtein, score_distribution};
+///
+/// assert_eq!(
+///     vec![0.0, 0.75, 1.0],
+///     score_distribution("cart", &["cart", "dogs", "card"], normalized_levenshtein)
+/// );
+/// ```
+pub fn score_distribution(query: &str, candidates: &[&str], metric: Scorer) -> Vec<f64> {
+    let mut scores: Vec<f64> = candidates
+        .iter()
+        .map(|candidate| metric(query, candidate))
+        .collect();
+    scores.sort_by(f64::total_cmp);
+    scores
+}
+
+/// Returns the `p`-th percentile (between 0 and 100) of `scores`,
+/// interpolating linearly between the two nearest scores. The scores don't
+/// have to be sorted. The 50th percentile is the median.
+///
+/// Returns `None` if `scores` is empty or `p` is not between 0 and 100
+/// (including NaN).
+///
+/// ```
+/// use strsim::percentile;
+///
+/// assert_eq!(Some(0.5), percentile(&[0.9, 0.1, 0.5], 50.0));
+/// assert_eq!(Some(0.5), percentile(&[0.25, 0.75], 50.0));
+/// assert_eq!(Some(0.9), percentile(&[0.9, 0.1, 0.5], 100.0));
+/// assert_eq!(None, percentile(&[], 50.0));
+/// ```
+pub fn percentile(scores: &[f64], p: f64) -> Option<f64> {
+    if scores.is_empty() || !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+/// Calculates a similarity of `exp(-k * levenshtein(a, b))`, which is exactly
+/// 1.0 for identical strings and decays exponentially with every edit,
+/// independently of the string lengths. Unlike normalized metrics, even a
+/// single edit between long strings gives a result clearly below 1.0.
+///
+/// `k` is the decay rate: every additional edit multiplies the similarity by
+/// `exp(-k)`, so `k = std::f64::consts::LN_2` halves it per edit. `k` should
+/// be positive, as a `k` of 0 gives 1.0 for all inputs.
+///
+/// ```
+/// use strsim::exact_decay_similarity;
+///
+/// let ln_2 = std::f64::consts::LN_2;
+/// assert_eq!(1.0, exact_decay_similarity("hello", "hello", ln_2));
+/// assert!((0.25 - exact_decay_similarity("hello", "help", ln_2)).abs() < 1e-12);
+/// ```
+pub fn exact_decay_similarity(a: &str, b: &str, k: f64) -> f64 {
+    (-k * levenshtein(a, b) as f64).exp()
+}
+
+/// Finds the two most similar items of a list according to the distance
+/// `metric` and returns their indices (smaller index first) with their
+/// distance, or `None` if there are fewer than two items. Of several equally
+/// close pairs, the one with the smallest indices is returned.
+///
+/// The items are compared in order of their length, and pairs whose lengths
+/// differ by more than the best distance found so far are skipped. This
+/// requires `metric` to be at least the difference of the character counts,
+/// which holds for edit distances like [`levenshtein`], [`osa_distance`] and
+/// [`damerau_levenshtein`].
+///
+/// ```
+/// use strsim::{closest_pair, levenshtein};
+///
+/// let items = ["apple", "banana", "cherry", "bananas"];
+/// assert_eq!(Some((1, 3, 1)), closest_pair(&items, levenshtein));
+/// ```
+pub fn closest_pair(
+    items: &[&str],
+    metric: fn(&str, &str) -> usize,
+) -> Option<(usize, usize, usize)> {
+    let mut by_len: Vec<(usize, usize)> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (item.chars().count(), i))
+        .collect();
+    by_len.sort_unstable();
+
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (k, &(len_a, a)) in by_len.iter().enumerate() {
+        for &(len_b, b) in &by_len[k + 1..] {
+            if best.is_some_and(|(_, _, distance)| len_b - len_a > distance) {
+                // all remaining items are at least as long
+                break;
+            }
+            let distance = metric(items[a], items[b]);
+            let (i, j) = (min(a, b), max(a, b));
+            let is_better = best.is_none_or(|(best_i, best_j, best_distance)| {
+                (distance, i, j) < (best_distance, best_i, best_j)
+            });
+            if is_better {
+                best = Some((i, j, distance));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! assert_delta {
+        ($x:expr, $y:expr) => {
+            assert_delta!($x, $y, 1e-5);
+        };
+        ($x:expr, $y:expr, $d:expr) => {
+            if ($x - $y).abs() > $d {
+                panic!(
+                    "assertion failed: actual: `{}`, expected: `{}`: \
+                    actual not within < {} of expected",
+                    $x, $y, $d
+                );
+            }
+        };
+    }
+
+    #[test]
+    fn bigrams_iterator() {
+        let mut bi = bigrams("abcde");
+
+        assert_eq!(Some(('a', 'b')), bi.next());
+        assert_eq!(Some(('b', 'c')), bi.next());
+        assert_eq!(Some(('c', 'd')), bi.next());
+        assert_eq!(Some(('d', 'e')), bi.next());
+        assert_eq!(None, bi.next());
+    }
+
+    fn assert_hamming_dist(dist: usize, str1: &str, str2: &str) {
+        assert_eq!(Ok(dist), hamming(str1, str2));
+    }
+
+    #[test]
+    fn hamming_empty() {
+        assert_hamming_dist(0, "", "")
+    }
+
+    #[test]
+    fn hamming_same() {
+        assert_hamming_dist(0, "hamming", "hamming")
+    }
+
+    #[test]
+    fn hamming_numbers() {
+        assert_eq!(Ok(1), generic_hamming(&[1, 2, 4], &[1, 2, 3]));
+    }
+
+    #[test]
+    fn hamming_diff() {
+        assert_hamming_dist(3, "hamming", "hammers")
+    }
+
+    #[test]
+    fn hamming_diff_multibyte() {
+        assert_hamming_dist(2, "hamming", "h香mmüng");
+    }
+
+    #[test]
+    fn hamming_unequal_length() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 3, len_b: 7 }),
+            generic_hamming("ham".chars(), "hamming".chars())
+        );
+    }
+
+    #[test]
+    fn hamming_unequal_length_multibyte() {
+        let err = hamming("h香mmüng", "hamming!!").unwrap_err();
+        assert_eq!(StrSimError::DifferentLengthArgs { len_a: 7, len_b: 9 }, err);
+        assert_eq!(
+            "Differing length arguments provided: 7 and 9",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn hamming_names() {
+        assert_hamming_dist(14, "Friedrich Nietzs", "Jean-Paul Sartre")
+    }
+
+    #[test]
+    fn jaro_both_empty() {
+        assert_eq!(1.0, jaro("", ""));
+    }
+
+    #[test]
+    fn jaro_first_empty() {
+        assert_eq!(0.0, jaro("", "jaro"));
+    }
+
+    #[test]
+    fn jaro_second_empty() {
+        assert_eq!(0.0, jaro("distance", ""));
+    }
+
+    #[test]
+    fn jaro_same() {
+        assert_eq!(1.0, jaro("jaro", "jaro"));
+    }
+
+    #[test]
+    fn jaro_multibyte() {
+        assert_delta!(0.818, jaro("testabctest", "testöঙ香test"), 0.001);
+        assert_delta!(0.818, jaro("testöঙ香test", "testabctest"), 0.001);
+    }
+
+    #[test]
+    fn jaro_diff_short() {
+        assert_delta!(0.767, jaro("dixon", "dicksonx"), 0.001);
+    }
+
+    #[test]
+    fn jaro_diff_one_character() {
+        assert_eq!(0.0, jaro("a", "b"));
+    }
+
+    #[test]
+    fn jaro_same_one_character() {
+        assert_eq!(1.0, jaro("a", "a"));
+    }
+
+    #[test]
+    fn generic_jaro_diff() {
+        assert_eq!(0.0, generic_jaro(&[1, 2], &[3, 4]));
+    }
+
+    #[test]
+    fn jaro_diff_one_and_two() {
+        assert_delta!(0.83, jaro("a", "ab"), 0.01);
+    }
+
+    #[test]
+    fn jaro_diff_two_and_one() {
+        assert_delta!(0.83, jaro("ab", "a"), 0.01);
+    }
+
+    #[test]
+    fn jaro_diff_no_transposition() {
+        assert_delta!(0.822, jaro("dwayne", "duane"), 0.001);
+    }
+
+    #[test]
+    fn jaro_diff_with_transposition() {
+        assert_delta!(0.944, jaro("martha", "marhta"), 0.001);
+        assert_delta!(0.6, jaro("a jke", "jane a k"), 0.001);
+    }
+
+    #[test]
+    fn jaro_names() {
+        assert_delta!(
+            0.392,
+            jaro("Friedrich Nietzsche", "Jean-Paul Sartre"),
+            0.001
+        );
+    }
+
+    #[test]
+    fn jaro_winkler_both_empty() {
+        assert_eq!(1.0, jaro_winkler("", ""));
+    }
+
+    #[test]
+    fn jaro_winkler_first_empty() {
+        assert_eq!(0.0, jaro_winkler("", "jaro-winkler"));
+    }
+
+    #[test]
+    fn jaro_winkler_second_empty() {
+        assert_eq!(0.0, jaro_winkler("distance", ""));
+    }
+
+    #[test]
+    fn jaro_winkler_same() {
+        assert_eq!(1.0, jaro_winkler("Jaro-Winkler", "Jaro-Winkler"));
+    }
+
+    #[test]
+    fn jaro_winkler_multibyte() {
+        assert_delta!(0.89, jaro_winkler("testabctest", "testöঙ香test"), 0.001);
+        assert_delta!(0.89, jaro_winkler("testöঙ香test", "testabctest"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_diff_short() {
+        assert_delta!(0.813, jaro_winkler("dixon", "dicksonx"), 0.001);
+        assert_delta!(0.813, jaro_winkler("dicksonx", "dixon"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_diff_one_character() {
+        assert_eq!(0.0, jaro_winkler("a", "b"));
+    }
+
+    #[test]
+    fn jaro_winkler_same_one_character() {
+        assert_eq!(1.0, jaro_winkler("a", "a"));
+    }
+
+    #[test]
+    fn jaro_winkler_diff_no_transposition() {
+        assert_delta!(0.84, jaro_winkler("dwayne", "duane"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_diff_with_transposition() {
+        assert_delta!(0.961, jaro_winkler("martha", "marhta"), 0.001);
+        assert_delta!(0.6, jaro_winkler("a jke", "jane a k"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_names() {
+        assert_delta!(
+            0.452,
+            jaro_winkler("Friedrich Nietzsche", "Fran-Paul Sartre"),
+            0.001
+        );
+    }
+
+    #[test]
+    fn jaro_winkler_long_prefix() {
+        assert_delta!(0.866, jaro_winkler("cheeseburger", "cheese fries"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_more_names() {
+        assert_delta!(0.868, jaro_winkler("Thorkel", "Thorgier"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_length_of_one() {
+        assert_delta!(0.738, jaro_winkler("Dinsdale", "D"), 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_very_long_prefix() {
+        assert_delta!(
+            0.98519,
+            jaro_winkler("thequickbrownfoxjumpedoverx", "thequickbrownfoxjumpedovery")
+        );
+    }
+
+    #[test]
+    fn levenshtein_empty() {
+        assert_eq!(0, levenshtein("", ""));
+    }
+
+    #[test]
+    fn levenshtein_same() {
+        assert_eq!(0, levenshtein("levenshtein", "levenshtein"));
+    }
+
+    #[test]
+    fn levenshtein_diff_short() {
+        assert_eq!(3, levenshtein("kitten", "sitting"));
+    }
+
+    #[test]
+    fn levenshtein_diff_with_space() {
+        assert_eq!(5, levenshtein("hello, world", "bye, world"));
+    }
+
+    #[test]
+    fn levenshtein_diff_multibyte() {
+        assert_eq!(3, levenshtein("öঙ香", "abc"));
+        assert_eq!(3, levenshtein("abc", "öঙ香"));
+    }
+
+    #[test]
+    fn levenshtein_diff_longer() {
+        let a = "The quick brown fox jumped over the angry dog.";
+        let b = "Lorem ipsum dolor sit amet, dicta latine an eam.";
+        assert_eq!(37, levenshtein(a, b));
+    }
+
+    #[test]
+    fn levenshtein_first_empty() {
+        assert_eq!(7, levenshtein("", "sitting"));
+    }
+
+    #[test]
+    fn levenshtein_second_empty() {
+        assert_eq!(6, levenshtein("kitten", ""));
+    }
+
+    #[test]
+    fn normalized_levenshtein_diff_short() {
+        assert_delta!(0.57142, normalized_levenshtein("kitten", "sitting"));
+    }
+
+    #[test]
+    fn normalized_levenshtein_for_empty_strings() {
+        assert_delta!(1.0, normalized_levenshtein("", ""));
+    }
+
+    #[test]
+    fn normalized_levenshtein_first_empty() {
+        assert_delta!(0.0, normalized_levenshtein("", "second"));
+    }
+
+    #[test]
+    fn normalized_levenshtein_second_empty() {
+        assert_delta!(0.0, normalized_levenshtein("first", ""));
+    }
+
+    #[test]
+    fn normalized_levenshtein_identical_strings() {
+        assert_delta!(1.0, normalized_levenshtein("identical", "identical"));
+    }
+
+    #[test]
+    fn osa_distance_empty() {
+        assert_eq!(0, osa_distance("", ""));
+    }
+
+    #[test]
+    fn osa_distance_same() {
+        assert_eq!(0, osa_distance("damerau", "damerau"));
+    }
+
+    #[test]
+    fn osa_distance_first_empty() {
+        assert_eq!(7, osa_distance("", "damerau"));
+    }
+
+    #[test]
+    fn osa_distance_second_empty() {
+        assert_eq!(7, osa_distance("damerau", ""));
+    }
+
+    #[test]
+    fn osa_distance_diff() {
+        assert_eq!(3, osa_distance("ca", "abc"));
+    }
+
+    #[test]
+    fn osa_distance_diff_short() {
+        assert_eq!(3, osa_distance("damerau", "aderua"));
+    }
+
+    #[test]
+    fn osa_distance_diff_reversed() {
+        assert_eq!(3, osa_distance("aderua", "damerau"));
+    }
+
+    #[test]
+    fn osa_distance_diff_multibyte() {
+        assert_eq!(3, osa_distance("öঙ香", "abc"));
+        assert_eq!(3, osa_distance("abc", "öঙ香"));
+    }
+
+    #[test]
+    fn osa_distance_diff_unequal_length() {
+        assert_eq!(6, osa_distance("damerau", "aderuaxyz"));
+    }
+
+    #[test]
+    fn osa_distance_diff_unequal_length_reversed() {
+        assert_eq!(6, osa_distance("aderuaxyz", "damerau"));
+    }
+
+    #[test]
+    fn osa_distance_diff_comedians() {
+        assert_eq!(5, osa_distance("Stewart", "Colbert"));
+    }
+
+    #[test]
+    fn osa_distance_many_transpositions() {
+        assert_eq!(4, osa_distance("abcdefghijkl", "bacedfgihjlk"));
+    }
+
+    #[test]
+    fn osa_distance_diff_longer() {
+        let a = "The quick brown fox jumped over the angry dog.";
+        let b = "Lehem ipsum dolor sit amet, dicta latine an eam.";
+        assert_eq!(36, osa_distance(a, b));
+    }
+
+    #[test]
+    fn osa_distance_beginning_transposition() {
+        assert_eq!(1, osa_distance("foobar", "ofobar"));
+    }
+
+    #[test]
+    fn osa_distance_end_transposition() {
+        assert_eq!(1, osa_distance("specter", "spectre"));
+    }
+
+    #[test]
+    fn osa_distance_restricted_edit() {
+        assert_eq!(4, osa_distance("a cat", "an abct"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_empty() {
+        assert_eq!(0, damerau_levenshtein("", ""));
+    }
+
+    #[test]
+    fn damerau_levenshtein_same() {
+        assert_eq!(0, damerau_levenshtein("damerau", "damerau"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_first_empty() {
+        assert_eq!(7, damerau_levenshtein("", "damerau"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_second_empty() {
+        assert_eq!(7, damerau_levenshtein("damerau", ""));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff() {
+        assert_eq!(2, damerau_levenshtein("ca", "abc"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_short() {
+        assert_eq!(3, damerau_levenshtein("damerau", "aderua"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_reversed() {
+        assert_eq!(3, damerau_levenshtein("aderua", "damerau"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_multibyte() {
+        assert_eq!(3, damerau_levenshtein("öঙ香", "abc"));
+        assert_eq!(3, damerau_levenshtein("abc", "öঙ香"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_unequal_length() {
+        assert_eq!(6, damerau_levenshtein("damerau", "aderuaxyz"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_unequal_length_reversed() {
+        assert_eq!(6, damerau_levenshtein("aderuaxyz", "damerau"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_comedians() {
+        assert_eq!(5, damerau_levenshtein("Stewart", "Colbert"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_many_transpositions() {
+        assert_eq!(4, damerau_levenshtein("abcdefghijkl", "bacedfgihjlk"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_diff_longer() {
+        let a = "The quick brown fox jumped over the angry dog.";
+        let b = "Lehem ipsum dolor sit amet, dicta latine an eam.";
+        assert_eq!(36, damerau_levenshtein(a, b));
+    }
+
+    #[test]
+    fn damerau_levenshtein_beginning_transposition() {
+        assert_eq!(1, damerau_levenshtein("foobar", "ofobar"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_end_transposition() {
+        assert_eq!(1, damerau_levenshtein("specter", "spectre"));
+    }
+
+    #[test]
+    fn damerau_levenshtein_unrestricted_edit() {
+        assert_eq!(3, damerau_levenshtein("a cat", "an abct"));
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_diff_short() {
+        assert_delta!(
+            0.27272,
+            normalized_damerau_levenshtein("levenshtein", "löwenbräu")
+        );
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_for_empty_strings() {
+        assert_delta!(1.0, normalized_damerau_levenshtein("", ""));
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_first_empty() {
+        assert_delta!(0.0, normalized_damerau_levenshtein("", "flower"));
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_second_empty() {
+        assert_delta!(0.0, normalized_damerau_levenshtein("tree", ""));
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_identical_strings() {
+        assert_delta!(
+            1.0,
+            normalized_damerau_levenshtein("sunglasses", "sunglasses")
+        );
+    }
+
+    #[test]
+    fn sorensen_dice_all() {
+        // test cases taken from
+        // https://github.com/aceakash/string-similarity/blob/f83ba3cd7bae874c20c429774e911ae8cff8bced/src/spec/index.spec.js#L11
+
+        assert_delta!(1.0, sorensen_dice("a", "a"));
+        assert_delta!(0.0, sorensen_dice("a", "b"));
+        assert_delta!(1.0, sorensen_dice("", ""));
+        assert_delta!(0.0, sorensen_dice("a", ""));
+        assert_delta!(0.0, sorensen_dice("", "a"));
+        assert_delta!(1.0, sorensen_dice("apple event", "apple    event"));
+        assert_delta!(0.90909, sorensen_dice("iphone", "iphone x"));
+        assert_delta!(0.0, sorensen_dice("french", "quebec"));
+        assert_delta!(1.0, sorensen_dice("france", "france"));
+        assert_delta!(0.2, sorensen_dice("fRaNce", "france"));
+        assert_delta!(0.8, sorensen_dice("healed", "sealed"));
+        assert_delta!(
+            0.78788,
+            sorensen_dice("web applications", "applications of the web")
+        );
+        assert_delta!(
+            0.92,
+            sorensen_dice(
+                "this will have a typo somewhere",
+                "this will huve a typo somewhere"
+            )
+        );
+        assert_delta!(
+            0.60606,
+            sorensen_dice(
+                "Olive-green table for sale, in extremely good condition.",
+                "For sale: table in very good  condition, olive green in colour."
+            )
+        );
+        assert_delta!(
+            0.25581,
+            sorensen_dice(
+                "Olive-green table for sale, in extremely good condition.",
+                "For sale: green Subaru Impreza, 210,000 miles"
+            )
+        );
+        assert_delta!(
+            0.14118,
+            sorensen_dice(
+                "Olive-green table for sale, in extremely good condition.",
+                "Wanted: mountain bike with at least 21 gears."
+            )
+        );
+        assert_delta!(
+            0.77419,
+            sorensen_dice("this has one extra word", "this has one word")
+        );
+    }
+
+    #[test]
+    fn shared_ngrams_bigrams() {
+        assert_eq!(1, shared_ngrams("night", "nacht", 2).unwrap());
+        assert_eq!(1, shared_ngrams("nacht", "night", 2).unwrap());
+    }
+
+    #[test]
+    fn shared_ngrams_repeated() {
+        assert_eq!(2, shared_ngrams("aaa", "aaaa", 2).unwrap());
+        assert_eq!(3, shared_ngrams("abab", "ababab", 2).unwrap());
+    }
+
+    #[test]
+    fn shared_ngrams_too_short() {
+        assert_eq!(0, shared_ngrams("", "", 2).unwrap());
+        assert_eq!(0, shared_ngrams("a", "a", 2).unwrap());
+    }
+
+    #[test]
+    fn shared_ngrams_multibyte() {
+        assert_eq!(2, shared_ngrams("öঙ香", "xöঙ香", 2).unwrap());
+    }
+
+    #[test]
+    fn jaro_winkler_pair_diff_short() {
+        let (jaro_sim, jaro_winkler_sim) = jaro_winkler_pair("dixon", "dicksonx");
+        assert_delta!(0.767, jaro_sim, 0.001);
+        assert_delta!(0.813, jaro_winkler_sim, 0.001);
+    }
+
+    #[test]
+    fn jaro_winkler_pair_matches_separate_calls() {
+        for (a, b) in [
+            ("martha", "marhta"),
+            ("", "jaro"),
+            ("a", "b"),
+            ("cheeseburger", "cheese fries"),
+        ] {
+            assert_eq!((jaro(a, b), jaro_winkler(a, b)), jaro_winkler_pair(a, b));
+        }
+    }
+
+    #[test]
+    fn all_scores_diff_short() {
+        let scores = all_scores("martha", "marhta");
+        assert_eq!(Some(2), scores.hamming);
+        assert_eq!(2, scores.levenshtein);
+        assert_eq!(1, scores.osa_distance);
+        assert_eq!(1, scores.damerau_levenshtein);
+        assert_delta!(0.944, scores.jaro, 0.001);
+        assert_delta!(0.961, scores.jaro_winkler, 0.001);
+    }
+
+    #[test]
+    fn all_scores_unequal_length() {
+        let scores = all_scores("kitten", "sitting");
+        assert_eq!(None, scores.hamming);
+        assert_delta!(0.57142, scores.normalized_levenshtein);
+    }
+
+    #[test]
+    fn levenshtein_classed_address() {
+        assert_eq!(0, levenshtein_classed("123 Main St.", "987 Main St"));
+        assert_eq!(1, levenshtein_classed("12 Main St.", "987 Main St"));
+        assert_eq!(4, levenshtein_classed("123 Main St.", "987 Elm St"));
+    }
+
+    #[test]
+    fn levenshtein_classed_by_custom() {
+        let ignore_case = |c: char| Some(c.to_ascii_lowercase());
+        assert_eq!(
+            0,
+            levenshtein_classed_by("Main St.", "main st.", ignore_case)
+        );
+        assert_eq!(
+            1,
+            levenshtein_classed_by("Main St.", "main st", ignore_case)
+        );
+    }
+
+    #[test]
+    fn levenshtein_os_valid_utf8() {
+        assert_eq!(
+            3,
+            levenshtein_os(OsStr::new("kitten"), OsStr::new("sitting"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn levenshtein_os_invalid_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let a = OsStr::from_bytes(b"file\xff\xfe.txt");
+        let b = OsStr::from_bytes(b"file\xff.txt");
+        assert_eq!(1, levenshtein_os(a, b));
+    }
+
+    #[test]
+    fn closest_key_misspelled() {
+        let mut map = HashMap::new();
+        map.insert("color".to_string(), 1);
+        map.insert("colour".to_string(), 2);
+        map.insert("size".to_string(), 3);
+
+        assert_eq!(Some(&"size".to_string()), closest_key("szie", &map, 2));
+        assert_eq!(Some(&"color".to_string()), closest_key("colr", &map, 1));
+        assert_eq!(None, closest_key("weight", &map, 2));
+    }
+
+    #[test]
+    fn closest_key_tie() {
+        let mut map = HashMap::new();
+        map.insert("bat".to_string(), ());
+        map.insert("cat".to_string(), ());
+
+        assert_eq!(Some(&"bat".to_string()), closest_key("at", &map, 1));
+    }
+
+    #[test]
+    fn closest_key_empty_map() {
+        let map: HashMap<String, ()> = HashMap::new();
+        assert_eq!(None, closest_key("anything", &map, 10));
+    }
+
+    #[test]
+    fn jaccard_bigrams() {
+        assert_delta!(1.0, jaccard("night", "night", 2).unwrap());
+        assert_delta!(1.0 / 7.0, jaccard("night", "nacht", 2).unwrap());
+        assert_delta!(0.0, jaccard("abc", "xyz", 2).unwrap());
+    }
+
+    #[test]
+    fn jaccard_too_short() {
+        assert_delta!(1.0, jaccard("", "", 2).unwrap());
+        assert_delta!(1.0, jaccard("a", "a", 2).unwrap());
+        assert_delta!(0.0, jaccard("a", "b", 2).unwrap());
+    }
+
+    #[test]
+    fn rolling_ngram_hashes_match_direct_hashes() {
+        // a rolled hash has to be identical to hashing the n-gram from scratch
+        let rolled = rolling_ngram_hashes("abcabd", 3);
+        let direct: HashSet<u64> = ["abc", "bca", "cab", "abd"]
+            .iter()
+            .flat_map(|gram| rolling_ngram_hashes(gram, 3))
+            .collect();
+        assert_eq!(direct, rolled);
+    }
+
+    #[test]
+    fn jaccard_hashed_approximates_jaccard() {
+        let a = "The quick brown fox jumped over the angry dog. It was not amused by this.";
+        let b = "The quick brown fox jumps over the lazy dog. It was very much amused.";
+        for n in 1..5 {
+            assert_delta!(
+                jaccard(a, b, n).unwrap(),
+                jaccard_hashed(a, b, n).unwrap(),
+                0.01
+            );
+        }
+    }
+
+    #[test]
+    fn jaro_winkler_prefix_by_ignore_case() {
+        let ignore_case = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+        let sim = jaro("MCDonald", "Mcdonald");
+
+        // the first four characters match case-insensitively
+        assert_delta!(
+            sim + 0.4 * (1.0 - sim),
+            jaro_winkler_prefix_by("MCDonald", "Mcdonald", ignore_case)
+        );
+        assert_delta!(
+            sim + 0.1 * (1.0 - sim),
+            jaro_winkler("MCDonald", "Mcdonald")
+        );
+    }
+
+    #[test]
+    fn jaro_winkler_prefix_by_exact_matches_jaro_winkler() {
+        let exact = |a: char, b: char| a == b;
+        assert_eq!(
+            jaro_winkler("cheeseburger", "cheese fries"),
+            jaro_winkler_prefix_by("cheeseburger", "cheese fries", exact)
+        );
+    }
+
+    fn count_edits(ops: &[EditOp]) -> usize {
+        ops.iter()
+            .filter(|op| !matches!(op, EditOp::Match(_)))
+            .count()
+    }
+
+    #[test]
+    fn operations_char() {
+        let ops = operations("kitten", "sitting");
+        assert_eq!(levenshtein("kitten", "sitting"), count_edits(&ops));
+        assert_eq!(
+            vec![
+                EditOp::Substitute("k".into(), "s".into()),
+                EditOp::Match("i".into()),
+                EditOp::Match("t".into()),
+                EditOp::Match("t".into()),
+                EditOp::Substitute("e".into(), "i".into()),
+                EditOp::Match("n".into()),
+                EditOp::Insert("g".into()),
+            ],
+            ops
+        );
+    }
+
+    #[test]
+    fn operations_empty() {
+        assert_eq!(Vec::<EditOp>::new(), operations("", ""));
+        assert_eq!(vec![EditOp::Insert("a".into())], operations("", "a"));
+        assert_eq!(vec![EditOp::Delete("a".into())], operations("a", ""));
+    }
+
+    #[test]
+    fn operations_with_unit_char_sentence() {
+        let a = "the cat sat";
+        let b = "the hat sat down";
+        let ops = operations_with_unit(a, b, Unit::Char);
+        assert_eq!(levenshtein(a, b), count_edits(&ops));
+    }
+
+    #[test]
+    fn operations_with_unit_word_sentence() {
+        assert_eq!(
+            vec![
+                EditOp::Match("the".into()),
+                EditOp::Substitute("cat".into(), "hat".into()),
+                EditOp::Match("sat".into()),
+                EditOp::Insert("down".into()),
+            ],
+            operations_with_unit("the cat sat", "the hat  sat down", Unit::Word)
+        );
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn operations_with_unit_grapheme_sentence() {
+        // "e\u{301}" is a single grapheme made of two chars
+        let a = "cafe\u{301} au lait";
+        let b = "cafe au lait";
+        assert_eq!(1, count_edits(&operations_with_unit(a, b, Unit::Grapheme)));
+        assert_eq!(
+            Some(&EditOp::Substitute("e\u{301}".into(), "e".into())),
+            operations_with_unit(a, b, Unit::Grapheme).get(3)
+        );
+        assert_eq!(1, count_edits(&operations_with_unit(a, b, Unit::Char)));
+        assert_eq!(
+            Some(&EditOp::Delete("\u{301}".into())),
+            operations_with_unit(a, b, Unit::Char).get(4)
+        );
+    }
+
+    #[test]
+    fn normalized_damerau_levenshtein_credits_transpositions() {
+        assert_delta!(0.5, normalized_damerau_levenshtein("ca", "ac"));
+        assert_delta!(0.0, normalized_levenshtein("ca", "ac"));
+        assert!(normalized_damerau_levenshtein("ca", "ac") > normalized_levenshtein("ca", "ac"));
+    }
+
+    #[test]
+    fn is_one_edit_away_identical() {
+        assert!(is_one_edit_away("", ""));
+        assert!(is_one_edit_away("pale", "pale"));
+    }
+
+    #[test]
+    fn is_one_edit_away_insert_delete() {
+        assert!(is_one_edit_away("pale", "ple"));
+        assert!(is_one_edit_away("ple", "pale"));
+        assert!(is_one_edit_away("pales", "pale"));
+        assert!(is_one_edit_away("", "a"));
+        assert!(is_one_edit_away("aaa", "aa"));
+    }
+
+    #[test]
+    fn is_one_edit_away_substitute() {
+        assert!(is_one_edit_away("pale", "bale"));
+        assert!(is_one_edit_away("pale", "palé"));
+    }
+
+    #[test]
+    fn is_one_edit_away_too_far() {
+        assert!(!is_one_edit_away("pale", "bake"));
+        assert!(!is_one_edit_away("pale", "pl"));
+        assert!(!is_one_edit_away("ab", "ba"));
+        assert!(!is_one_edit_away("abc", "bcd"));
+    }
+
+    #[test]
+    fn is_one_edit_away_matches_levenshtein() {
+        let words = ["", "a", "ab", "ba", "aba", "abab", "bab", "aab", "abb"];
+        for a in words {
+            for b in words {
+                assert_eq!(levenshtein(a, b) <= 1, is_one_edit_away(a, b), "{a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn hamming_ignore_case_same() {
+        assert_eq!(Ok(0), hamming_ignore_case("ABCdef", "abcDEF"));
+        assert_eq!(Ok(0), hamming_ignore_case("ÖSTERREICH", "österreich"));
+    }
+
+    #[test]
+    fn hamming_ignore_case_diff() {
+        assert_eq!(Ok(3), hamming_ignore_case("Hamming", "hAMMERS"));
+    }
+
+    #[test]
+    fn hamming_ignore_case_multi_char_lowercase() {
+        // 'İ' lowercases to two chars but still occupies a single position,
+        // so the case-only differences after it line up
+        assert_eq!(Ok(0), hamming_ignore_case("İxY", "İXy"));
+        assert_eq!(Ok(1), hamming_ignore_case("İxY", "iXy"));
+        assert_eq!(Ok(1), hamming_ignore_case("İx", "ix"));
+        // the lowercase form of 'İ' is two chars, not a single position
+        assert!(hamming_ignore_case("İx", "i\u{307}x").is_err());
+        assert_eq!(Ok(0), hamming_ignore_case("STRAẞE", "straße"));
+    }
+
+    #[test]
+    fn hamming_ignore_case_unequal_length() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 3, len_b: 4 }),
+            hamming_ignore_case("ABC", "abcd")
+        );
+    }
+
+    #[test]
+    fn ngram_index_query() {
+        let mut index = NgramIndex::new(2);
+        index.insert("a", "night");
+        index.insert("b", "nacht");
+        index.insert("c", "knight");
+        index.insert("d", "day");
+
+        assert_eq!(vec!["a", "c"], index.query("nigth", 2));
+        assert_eq!(vec!["a", "c"], index.query("nigth", 1));
+        assert_eq!(vec!["a", "b", "c"], index.query("night", 1));
+        assert_eq!(vec!["a", "c"], index.query("night", 4));
+        assert_eq!(Vec::<&str>::new(), index.query("night", 5));
+    }
+
+    #[test]
+    fn ngram_index_query_min_shared_zero() {
+        let mut index = NgramIndex::new(2);
+        index.insert("a", "night");
+        index.insert("b", "day");
+        index.insert("c", "");
+
+        assert_eq!(vec!["a", "b", "c"], index.query("night", 0));
+        assert_eq!(vec!["a", "b", "c"], index.query("", 0));
+    }
+
+    #[test]
+    fn ngram_index_repeated_ngrams() {
+        let mut index = NgramIndex::new(2);
+        index.insert(0, "aaaa");
+        index.insert(1, "ab");
+
+        // repeated n-grams are only counted once
+        assert_eq!(vec![0], index.query("aaaaaa", 1));
+        assert_eq!(Vec::<i32>::new(), index.query("aaaaaa", 2));
+    }
+
+    #[test]
+    fn ngram_index_empty() {
+        let index: NgramIndex<usize> = NgramIndex::new(3);
+        assert_eq!(Vec::<usize>::new(), index.query("anything", 0));
+    }
+
+    #[test]
+    fn generic_damerau_levenshtein_transposition() {
+        let a: &[u32] = &[1, 2, 3, 4];
+        let b: &[u32] = &[1, 3, 2, 4];
+        assert_eq!(1, generic_damerau_levenshtein(a, b));
+        assert_eq!(2, generic_damerau_levenshtein(&[1_u32, 2], &[2, 3, 1]));
+    }
+
+    #[test]
+    fn generic_damerau_levenshtein_without_clone() {
+        #[derive(PartialEq, Eq, Hash)]
+        struct Token(u32);
+
+        let a = [Token(1), Token(2), Token(3)];
+        let b = [Token(2), Token(1), Token(3)];
+        assert_eq!(1, generic_damerau_levenshtein(&a, &b));
+    }
+
+    #[test]
+    fn line_levenshtein_insert_and_change() {
+        let a = "first line\nsecond line\nthird line\n";
+        let b = "first line\ninserted line\nsecond line\nthird line changed\n";
+        assert_eq!(2, line_levenshtein(a, b));
+        assert_eq!(2, line_levenshtein(b, a));
+    }
+
+    #[test]
+    fn line_levenshtein_same() {
+        assert_eq!(0, line_levenshtein("", ""));
+        assert_eq!(0, line_levenshtein("a\nb\nc", "a\nb\nc"));
+    }
+
+    #[test]
+    fn line_levenshtein_trailing_newline() {
+        // the empty line after a trailing newline counts as a line
+        assert_eq!(1, line_levenshtein("a\nb", "a\nb\n"));
+    }
+
+    #[test]
+    fn blend_midpoint() {
+        let a = "dixon";
+        let b = "dicksonx";
+        let midpoint = (jaro_winkler(a, b) + normalized_levenshtein(a, b)) / 2.0;
+
+        assert_delta!(
+            midpoint,
+            blend(a, b, &[(jaro_winkler, 0.5), (normalized_levenshtein, 0.5)])
+        );
+        assert_delta!(
+            midpoint,
+            blend(a, b, &[(jaro_winkler, 2.0), (normalized_levenshtein, 2.0)])
+        );
+    }
+
+    #[test]
+    fn blend_single_scorer() {
+        assert_delta!(
+            jaro("martha", "marhta"),
+            blend("martha", "marhta", &[(jaro, 7.0)])
+        );
+    }
+
+    #[test]
+    fn blend_without_weight() {
+        assert_delta!(0.0, blend("a", "a", &[]));
+        assert_delta!(0.0, blend("a", "a", &[(jaro, 0.0)]));
+    }
+
+    #[test]
+    fn hamming_array_same() {
+        let digest = [0xab_u8; 32];
+        assert_eq!(0, hamming_array(&digest, &digest));
+    }
+
+    #[test]
+    fn hamming_array_diff() {
+        let a = [0_u8; 32];
+        let mut b = [0_u8; 32];
+        b[0] = 1;
+        b[17] = 0xff;
+        b[31] = 0x80;
+        assert_eq!(3, hamming_array(&a, &b));
+        assert_eq!(Ok(3), generic_hamming(&a, &b));
+    }
+
+    #[test]
+    fn hamming_array_empty() {
+        assert_eq!(0, hamming_array(&[], &[]));
+    }
+
+    #[test]
+    fn token_levenshtein_substitution() {
+        let a = ["fn", "main", "(", ")", "{", "}"];
+        let b = ["fn", "test", "(", ")", "{", "}"];
+        assert_eq!(1, token_levenshtein(&a, &b));
+    }
+
+    #[test]
+    fn token_levenshtein_insert_delete() {
+        assert_eq!(2, token_levenshtein(&["a", "b"], &["b", "c"]));
+        assert_eq!(3, token_levenshtein(&[], &["x", "y", "z"]));
+        assert_eq!(0, token_levenshtein(&[], &[]));
+    }
+
+    #[test]
+    fn jaro_winkler_upper_bound_holds() {
+        let words = [
+            "",
+            "a",
+            "ab",
+            "dixon",
+            "dicksonx",
+            "martha",
+            "marhta",
+            "cheeseburger",
+        ];
+        for a in words {
+            for b in words {
+                let bound = jaro_winkler_upper_bound(a.chars().count(), b.chars().count());
+                assert!(jaro_winkler(a, b) <= bound, "{} {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn jaro_winkler_above_threshold() {
+        let candidates = [
+            "dixon",
+            "dicksonx",
+            "dixen",
+            "d",
+            "nixon",
+            "dixonxxxxxxxxxx",
+        ];
+        assert_eq!(
+            vec!["dixon", "dixen", "nixon", "dixonxxxxxxxxxx"],
+            jaro_winkler_above("dixon", &candidates, 0.85)
+        );
+        assert_eq!(
+            vec!["dixon", "dixen"],
+            jaro_winkler_above("dixon", &candidates, 0.9)
+        );
+        assert_eq!(vec!["dixon"], jaro_winkler_above("dixon", &candidates, 1.0));
+    }
+
+    #[test]
+    fn jaro_winkler_above_empty() {
+        assert_eq!(Vec::<&str>::new(), jaro_winkler_above("dixon", &[], 0.0));
+        assert_eq!(vec![""], jaro_winkler_above("", &["", "a"], 0.5));
+    }
+
+    #[test]
+    fn levenshtein_cancellable_not_cancelled() {
+        let a = "The quick brown fox jumped over the angry dog.";
+        let b = "Lorem ipsum dolor sit amet, dicta latine an eam.";
+        assert_eq!(Some(37), levenshtein_cancellable(a, b, || false));
+        assert_eq!(Some(0), levenshtein_cancellable("", "", || true));
+    }
+
+    #[test]
+    fn levenshtein_cancellable_cancelled_after_rows() {
+        use std::cell::Cell;
+
+        let rows = Cell::new(0);
+        let should_cancel = || {
+            rows.set(rows.get() + 1);
+            rows.get() > 3
+        };
+
+        assert_eq!(
+            None,
+            levenshtein_cancellable("kitten", "sitting", should_cancel)
+        );
+        assert_eq!(4, rows.get());
+    }
+
+    /// Generates a pseudo random string from a small alphabet.
+    fn random_string(seed: &mut u64, len: usize) -> String {
+        (0..len)
+            .map(|_| {
+                *seed = seed
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                ['a', 'b', 'c', 'd', 'ö', '香'][(*seed >> 33) as usize % 6]
+            })
+            .collect()
+    }
+
+    /// Randomly substitutes characters of `s`.
+    fn mutate(seed: &mut u64, s: &str, edits: usize) -> String {
+        let mut chars: Vec<char> = s.chars().collect();
+        for _ in 0..edits {
+            if chars.is_empty() {
+                break;
+            }
+            let replacement = random_string(seed, 1).chars().next().unwrap();
+            let pos = (*seed >> 33) as usize % chars.len();
+            chars[pos] = replacement;
+        }
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn levenshtein_auto_short() {
+        let mut seed = 1;
+        for len in [0, 1, 5, 31, 63, 64] {
+            for other_len in [0, 1, 10, 64, 65, 200] {
+                let a = random_string(&mut seed, len);
+                let b = random_string(&mut seed, other_len);
+                assert_eq!(levenshtein(&a, &b), levenshtein_auto(&a, &b));
+                assert_eq!(levenshtein(&b, &a), levenshtein_auto(&b, &a));
+            }
+        }
+    }
+
+    #[test]
+    fn levenshtein_auto_long_and_close() {
+        let mut seed = 2;
+        for len in [65, 100, 300] {
+            for edits in [0, 1, 10, 100] {
+                let a = random_string(&mut seed, len);
+                let b = mutate(&mut seed, &a, edits);
+                let c = format!("{}{}", b, random_string(&mut seed, len / 5));
+                assert_eq!(levenshtein(&a, &b), levenshtein_auto(&a, &b));
+                assert_eq!(levenshtein(&a, &c), levenshtein_auto(&a, &c));
+                assert_eq!(levenshtein(&c, &a), levenshtein_auto(&c, &a));
+            }
+        }
+    }
+
+    #[test]
+    fn levenshtein_auto_long_and_different_lengths() {
+        let mut seed = 3;
+        let a = random_string(&mut seed, 80);
+        let b = random_string(&mut seed, 250);
+        assert_eq!(levenshtein(&a, &b), levenshtein_auto(&a, &b));
+    }
+
+    #[test]
+    fn levenshtein_banded_limit() {
+        let a: Vec<char> = "kitten".chars().collect();
+        let b: Vec<char> = "sitting".chars().collect();
+        assert_eq!(None, levenshtein_banded(&a, &b, 2));
+        assert_eq!(Some(3), levenshtein_banded(&a, &b, 3));
+        assert_eq!(Some(3), levenshtein_banded(&a, &b, 10));
+    }
+
+    #[test]
+    fn hamming_positions_diff() {
+        assert_eq!(Ok(vec![2, 3, 4]), hamming_positions("karolin", "kathrin"));
+        assert_eq!(Ok(vec![1]), hamming_positions("h香mming", "hamming"));
+        assert_eq!(
+            hamming("karolin", "kathrin"),
+            hamming_positions("karolin", "kathrin").map(|p| p.len())
+        );
+    }
+
+    #[test]
+    fn hamming_positions_same() {
+        assert_eq!(Ok(vec![]), hamming_positions("", ""));
+        assert_eq!(Ok(vec![]), hamming_positions("hamming", "hamming"));
+    }
+
+    #[test]
+    fn hamming_positions_unequal_length() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 3, len_b: 7 }),
+            hamming_positions("ham", "hamming")
+        );
+    }
+
+    #[test]
+    fn anagram_distance_anagrams() {
+        assert_eq!(0, anagram_distance("", ""));
+        assert_eq!(0, anagram_distance("listen", "silent"));
+        assert_eq!(0, anagram_distance("dormitory", "dirtyroom"));
+        assert_eq!(0, anagram_distance("öঙ香", "香öঙ"));
+    }
+
+    #[test]
+    fn anagram_distance_near_anagrams() {
+        // remove 'e' and insert 'a'
+        assert_eq!(2, anagram_distance("listen", "silant"));
+        // insert 's'
+        assert_eq!(1, anagram_distance("listen", "silents"));
+        assert_eq!(4, anagram_distance("ab", "cd"));
+        assert_eq!(3, anagram_distance("", "abc"));
+    }
+
+    #[test]
+    fn weighted_hamming_non_uniform() {
+        // the record is "<3 char code><1 char flag>", the code matters most
+        let weights = [3.0, 3.0, 3.0, 0.5];
+        assert_eq!(Ok(0.5), weighted_hamming("ABCy", "ABCn", &weights));
+        assert_eq!(Ok(3.0), weighted_hamming("ABCy", "ABDy", &weights));
+        assert_eq!(Ok(0.0), weighted_hamming("ABCy", "ABCy", &weights));
+    }
+
+    #[test]
+    fn weighted_hamming_uniform_matches_hamming() {
+        let weights = [1.0; 7];
+        assert_eq!(Ok(3.0), weighted_hamming("hamming", "hammers", &weights));
+    }
+
+    #[test]
+    fn weighted_hamming_length_mismatch() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 3, len_b: 4 }),
+            weighted_hamming("abc", "abcd", &[1.0; 4])
+        );
+    }
+
+    #[test]
+    fn weighted_hamming_weight_count_mismatch() {
+        assert_eq!(
+            Err(StrSimError::WeightCountMismatch {
+                expected: 3,
+                actual: 2
+            }),
+            weighted_hamming("abc", "abd", &[1.0; 2])
+        );
+        let err = weighted_hamming("abc", "abd", &[1.0; 4]).unwrap_err();
+        assert_eq!(
+            StrSimError::WeightCountMismatch {
+                expected: 3,
+                actual: 4
+            },
+            err
+        );
+        assert_eq!("Expected 3 weights, got 4", err.to_string());
+    }
+
+    #[test]
+    fn subsequence_distance_embedded() {
+        assert_eq!(Some(2), subsequence_distance("ace", "abcde"));
+        assert_eq!(Some(0), subsequence_distance("abcde", "abcde"));
+        assert_eq!(Some(5), subsequence_distance("", "abcde"));
+        assert_eq!(Some(0), subsequence_distance("", ""));
+        assert_eq!(Some(1), subsequence_distance("香港", "香x港"));
+    }
+
+    #[test]
+    fn subsequence_distance_not_embedded() {
+        assert_eq!(None, subsequence_distance("aec", "abcde"));
+        assert_eq!(None, subsequence_distance("abcdef", "abcde"));
+        assert_eq!(None, subsequence_distance("aa", "a"));
+        assert_eq!(None, subsequence_distance("a", ""));
+    }
+
+    #[test]
+    fn fuzzy_contains_typo() {
+        let haystack = "the quick kittin jumped over the lazy dog";
+        assert!(fuzzy_contains(haystack, "kitten", 1));
+        assert!(!fuzzy_contains(haystack, "kitten", 0));
+        assert!(fuzzy_contains(haystack, "kitten", 2));
+    }
+
+    #[test]
+    fn fuzzy_contains_exact() {
+        assert!(fuzzy_contains("abcdef", "cde", 0));
+        assert!(fuzzy_contains("abcdef", "abcdef", 0));
+        assert!(!fuzzy_contains("abcdef", "xyz", 2));
+        assert!(fuzzy_contains("abcdef", "xyz", 3));
+    }
+
+    #[test]
+    fn fuzzy_contains_insertion_and_deletion() {
+        assert!(fuzzy_contains("a kiten here", "kitten", 1));
+        assert!(fuzzy_contains("a kitteen here", "kitten", 1));
+        assert!(!fuzzy_contains("a kotxen here", "kitten", 1));
+        assert!(fuzzy_contains("a kotxen here", "kitten", 2));
+    }
+
+    #[test]
+    fn fuzzy_contains_empty() {
+        assert!(fuzzy_contains("", "", 0));
+        assert!(fuzzy_contains("abc", "", 0));
+        assert!(!fuzzy_contains("", "a", 0));
+        assert!(fuzzy_contains("", "a", 1));
+    }
+
+    #[test]
+    fn fuzzy_find_span() {
+        let haystack = "the quick kittin jumped";
+        assert_eq!(Some((10, 16, 1)), fuzzy_find(haystack, "kitten", 1));
+        assert_eq!(
+            "kittin",
+            haystack.chars().skip(10).take(6).collect::<String>()
+        );
+        assert_eq!(None, fuzzy_find(haystack, "kitten", 0));
+    }
+
+    #[test]
+    fn fuzzy_find_exact() {
+        assert_eq!(Some((2, 5, 0)), fuzzy_find("abcdefcde", "cde", 0));
+        assert_eq!(Some((1, 3, 0)), fuzzy_find("香港香港", "港香", 0));
+    }
+
+    #[test]
+    fn fuzzy_find_insertion_in_haystack() {
+        assert_eq!(Some((1, 5, 1)), fuzzy_find("xabbcx", "abbbc", 1));
+        assert_eq!(Some((2, 9, 1)), fuzzy_find("a kittxen here", "kitten", 2));
+        // "ab" already is within one error of "abc" and ends first
+        assert_eq!(Some((0, 2, 1)), fuzzy_find("abbc", "abc", 1));
+    }
+
+    #[test]
+    fn fuzzy_find_empty() {
+        assert_eq!(Some((0, 0, 0)), fuzzy_find("abc", "", 0));
+        assert_eq!(Some((0, 0, 1)), fuzzy_find("", "a", 1));
+        assert_eq!(None, fuzzy_find("", "a", 0));
+    }
+
+    #[test]
+    fn fuzzy_find_matches_fuzzy_contains() {
+        let haystack = "the quick brown fox jumped over the lazy dog";
+        for needle in ["quack", "brwn", "fx", "lazy dgo", "cat", "jumped over"] {
+            for max_errors in 0..3 {
+                assert_eq!(
+                    fuzzy_contains(haystack, needle, max_errors),
+                    fuzzy_find(haystack, needle, max_errors).is_some()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cosine_ngram_idf_without_weights() {
+        let idf = HashMap::new();
+        assert_delta!(1.0, cosine_ngram_idf("night", "night", 2, &idf).unwrap());
+        assert_delta!(0.25, cosine_ngram_idf("night", "nacht", 2, &idf).unwrap());
+        assert_delta!(0.0, cosine_ngram_idf("abc", "xyz", 2, &idf).unwrap());
+    }
+
+    #[test]
+    fn cosine_ngram_idf_changes_ranking() {
+        let query = "the report";
+        let candidates = ["the sport", "tax report"];
+
+        let idf = HashMap::new();
+        assert!(
+            cosine_ngram_idf(query, candidates[0], 2, &idf).unwrap()
+                > cosine_ngram_idf(query, candidates[1], 2, &idf).unwrap()
+        );
+
+        // "th", "he" and "e " are very common and therefore nearly worthless
+        let mut idf = HashMap::new();
+        for gram in ["th", "he", "e "] {
+            idf.insert(gram.to_string(), 0.01);
+        }
+        assert!(
+            cosine_ngram_idf(query, candidates[0], 2, &idf).unwrap()
+                < cosine_ngram_idf(query, candidates[1], 2, &idf).unwrap()
+        );
+    }
+
+    #[test]
+    fn cosine_ngram_idf_too_short() {
+        let idf = HashMap::new();
+        assert_delta!(1.0, cosine_ngram_idf("a", "a", 2, &idf).unwrap());
+        assert_delta!(0.0, cosine_ngram_idf("a", "b", 2, &idf).unwrap());
+        assert_delta!(0.0, cosine_ngram_idf("a", "ab", 2, &idf).unwrap());
+    }
+
+    #[test]
+    fn lcs_len_slices() {
+        let a: Vec<char> = "ABCBDAB".chars().collect();
+        let b: Vec<char> = "BDCAB".chars().collect();
+        assert_eq!(4, lcs_len(&a, &b));
+        assert_eq!(0, lcs_len::<u8>(&[], &[1, 2]));
+    }
+
+    #[test]
+    fn token_set_ratio_subset() {
+        assert_eq!(
+            100,
+            token_set_ratio("fuzzy was a bear", "fuzzy fuzzy was a bear")
+        );
+        assert_eq!(
+            100,
+            token_set_ratio("new york mets", "new york mets vs atlanta braves")
+        );
+    }
+
+    #[test]
+    fn token_set_ratio_reordered() {
+        assert_eq!(100, token_set_ratio("New York Mets", "mets, new york"));
+    }
+
+    #[test]
+    fn token_set_ratio_partial_overlap() {
+        // "new york" compared to "new york mets": 2 * 8 / 21
+        assert_eq!(76, token_set_ratio("new york mets", "new york yankees"));
+        assert_eq!(0, token_set_ratio("", "anything"));
+        assert_eq!(0, token_set_ratio("!!", "??"));
+    }
+
+    #[test]
+    fn token_store_matches_token_set_ratio() {
+        let candidates = [
+            "new york mets",
+            "new york yankees",
+            "atlanta braves",
+            "",
+            "Mets of New York",
+        ];
+        let store = TokenStore::new(&candidates);
+
+        for query in ["new york", "braves atlanta", "mets", ""] {
+            let expected: Vec<(&str, u8)> = candidates
+                .iter()
+                .map(|&candidate| (candidate, token_set_ratio(query, candidate)))
+                .collect();
+            assert_eq!(expected, store.query(query));
+        }
+    }
+
+    #[test]
+    fn glob_levenshtein_star() {
+        assert_eq!(0, glob_levenshtein("a*c", "abbbc"));
+        assert_eq!(0, glob_levenshtein("a*c", "ac"));
+        assert_eq!(1, glob_levenshtein("a*c", "abbbd"));
+        assert_eq!(0, glob_levenshtein("*", ""));
+        assert_eq!(0, glob_levenshtein("*", "anything"));
+        assert_eq!(0, glob_levenshtein("*.rs", "lib.rs"));
+        assert_eq!(1, glob_levenshtein("*.rs", "lib.rc"));
+    }
+
+    #[test]
+    fn glob_levenshtein_without_star() {
+        assert_eq!(
+            levenshtein("kitten", "sitting"),
+            glob_levenshtein("kitten", "sitting")
+        );
+        assert_eq!(3, glob_levenshtein("abc", ""));
+        assert_eq!(3, glob_levenshtein("", "abc"));
+    }
+
+    #[test]
+    fn glob_levenshtein_multiple_stars() {
+        assert_eq!(0, glob_levenshtein("a*b*c", "axxbyyc"));
+        assert_eq!(1, glob_levenshtein("a*b*c", "axxyyc"));
+        assert_eq!(0, glob_levenshtein("**", "abc"));
+    }
+
+    #[test]
+    fn glob_levenshtein_stars_against_empty_text() {
+        assert_eq!(0, glob_levenshtein("*", ""));
+        assert_eq!(1, glob_levenshtein("*a", ""));
+        assert_eq!(3, glob_levenshtein("*abc", ""));
+        assert_eq!(2, glob_levenshtein("a*b", ""));
+        assert_eq!(2, glob_levenshtein("a**b*", ""));
+    }
+
+    #[test]
+    fn jaro_windowed_default_window() {
+        let pairs = [
+            ("dixon", "dicksonx"),
+            ("martha", "marhta"),
+            ("a", "ab"),
+            ("Friedrich Nietzsche", "Jean-Paul Sartre"),
+            ("", ""),
+            ("", "a"),
+        ];
+        for (a, b) in pairs {
+            let window = (max(a.chars().count(), b.chars().count()) / 2).saturating_sub(1);
+            assert_eq!(jaro(a, b), jaro_windowed(a, b, window));
+        }
+    }
+
+    #[test]
+    fn jaro_windowed_wider_window() {
+        // with the default window of 0 the swapped characters don't match at all
+        assert_eq!(0.0, jaro("ab", "ba"));
+        assert_delta!(5.0 / 6.0, jaro_windowed("ab", "ba", 1));
+    }
+
+    #[test]
+    fn similarity_join_fuzzy_matches() {
+        let left = ["Jon Smith", "Jane Doe", "Bob"];
+        let right = ["John Smith", "Jane Dow", "Alice", "Bobby"];
+
+        let pairs: Vec<(&str, &str)> = similarity_join(&left, &right, 0.85, jaro_winkler)
+            .into_iter()
+            .map(|(l, r, _)| (l, r))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Jon Smith", "John Smith"),
+                ("Jane Doe", "Jane Dow"),
+                ("Bob", "Bobby"),
+            ],
+            pairs
+        );
+    }
+
+    #[test]
+    fn similarity_join_matches_brute_force() {
+        let left = ["abc", "acb", "", "xyz", "香港"];
+        let right = ["bca", "", "zyx", "香", "abcd"];
+        for threshold in [0.0, 0.3, 0.6, 1.0] {
+            for metric in [jaro, jaro_winkler, normalized_levenshtein] {
+                let mut expected = Vec::new();
+                for &l in &left {
+                    for &r in &right {
+                        let score = metric(l, r);
+                        if score >= threshold {
+                            expected.push((l, r, score));
+                        }
+                    }
+                }
+                assert_eq!(expected, similarity_join(&left, &right, threshold, metric));
+            }
+        }
+    }
+
+    #[test]
+    fn similarity_join_scores_pairs_without_shared_characters() {
+        assert_eq!(
+            vec![("JOHN", "john", 1.0)],
+            similarity_join(&["JOHN"], &["john"], 0.5, name_similarity)
+        );
+    }
+
+    #[test]
+    fn osa_window_distance_two_swap() {
+        assert_eq!(2, osa_window("abc", "cba", 1));
+        assert_eq!(1, osa_window("abc", "cba", 2));
+        assert_eq!(1, osa_window("abc", "cba", 5));
+        assert_eq!(2, osa_window("abc", "cba", 0));
+    }
+
+    #[test]
+    fn osa_window_middle_has_to_match() {
+        // swapping 'a' and 'c' doesn't help when the middle changed as well
+        assert_eq!(levenshtein("abc", "cxa"), osa_window("abc", "cxa", 2));
+        assert_eq!(1, osa_window("abcd", "dbca", 3));
+        assert_eq!(2, osa_window("abcd", "dbca", 2));
+    }
+
+    #[test]
+    fn osa_window_one_is_osa() {
+        let pairs = [
+            ("ab", "bca"),
+            ("damerau", "aderua"),
+            ("a cat", "an abct"),
+            ("abcdefghijkl", "bacedfgihjlk"),
+            ("", "abc"),
+            ("öঙ香", "香ঙö"),
+        ];
+        for (a, b) in pairs {
+            assert_eq!(osa_distance(a, b), osa_window(a, b, 1));
+            assert_eq!(levenshtein(a, b), osa_window(a, b, 0));
+        }
+    }
+
+    #[test]
+    fn distance_trait_objects() {
+        let metrics: [(&dyn Distance, f64, f64); 5] = [
+            (&Levenshtein::new(), 3.0, 0.57142),
+            (&DamerauLevenshtein, 3.0, 0.57142),
+            (&Hamming, 3.0, 0.57142),
+            (&Jaro::new(), 0.74603, 0.74603),
+            (&Lcs, 5.0, 0.61538),
+        ];
+
+        for (metric, raw, normalized) in metrics {
+            assert_delta!(raw, metric.raw("kitten", "sitting"));
+            assert_delta!(normalized, metric.normalized("kitten", "sitting"));
+        }
+    }
+
+    #[test]
+    fn distance_trait_same_strings() {
+        let metrics: [&dyn Distance; 5] = [
+            &Levenshtein::new(),
+            &DamerauLevenshtein,
+            &Hamming,
+            &Jaro::new(),
+            &Lcs,
+        ];
+        for metric in metrics {
+            assert_delta!(1.0, metric.normalized("", ""));
+            assert_delta!(1.0, metric.normalized("same", "same"));
+        }
+    }
+
+    #[test]
+    fn hamming_distance_trait_matches_hamming() {
+        assert_delta!(3.0, Hamming.raw("hamming", "hammers"));
+        assert_delta!(4.0, Hamming.raw("ham", "hamming"));
+    }
+
+    #[test]
+    fn levenshtein_directional_unit_costs() {
+        let distance = levenshtein_directional("kitten", "sitting", |_| 1.0, |_| 1.0, |_, _| 1.0);
+        assert_delta!(3.0, distance);
+    }
+
+    #[test]
+    fn levenshtein_directional_cheap_vowel_insertion() {
+        let is_vowel = |c: char| "aeiou".contains(c);
+        let insert_cost = |c: char| if is_vowel(c) { 0.5 } else { 1.0 };
+        let delete_cost = |c: char| if is_vowel(c) { 1.0 } else { 2.0 };
+
+        assert_delta!(
+            1.0,
+            levenshtein_directional("bttr", "butter", insert_cost, delete_cost, |_, _| 3.0)
+        );
+        // the other direction deletes the vowels and is more expensive
+        assert_delta!(
+            2.0,
+            levenshtein_directional("butter", "bttr", insert_cost, delete_cost, |_, _| 3.0)
+        );
+        assert_delta!(
+            1.0,
+            levenshtein_directional("hallo", "hello", insert_cost, delete_cost, |_, _| 1.0)
+        );
+        // deleting 'a' and inserting 'e' is cheaper than substituting it
+        assert_delta!(
+            1.5,
+            levenshtein_directional("hallo", "hello", insert_cost, delete_cost, |_, _| 3.0)
+        );
+    }
+
+    #[test]
+    fn levenshtein_directional_empty() {
+        let distance = levenshtein_directional("", "abc", |_| 0.5, |_| 1.0, |_, _| 1.0);
+        assert_delta!(1.5, distance);
+        let distance = levenshtein_directional("abc", "", |_| 0.5, |_| 1.0, |_, _| 1.0);
+        assert_delta!(3.0, distance);
+    }
+
+    #[test]
+    fn cluster_near_duplicates_groups() {
+        let items = [
+            "Jonathan Smith",
+            "apple pie",
+            "Jonathon Smith",
+            "Jonathan Smyth",
+        ];
+        assert_eq!(
+            vec![vec![0, 2, 3], vec![1]],
+            cluster_near_duplicates(&items, 0.9, jaro_winkler)
+        );
+    }
+
+    #[test]
+    fn cluster_near_duplicates_transitive() {
+        // "aaaa" and "bbaa" are not similar, but both are similar to "abaa"
+        let items = ["aaaa", "bbaa", "abaa"];
+        assert_eq!(
+            vec![vec![0, 1, 2]],
+            cluster_near_duplicates(&items, 0.75, normalized_levenshtein)
+        );
+        assert_eq!(
+            vec![vec![0], vec![1], vec![2]],
+            cluster_near_duplicates(&items, 1.0, normalized_levenshtein)
+        );
+    }
+
+    #[test]
+    fn cluster_near_duplicates_empty() {
+        assert_eq!(
+            Vec::<Vec<usize>>::new(),
+            cluster_near_duplicates(&[], 0.5, jaro)
+        );
+    }
+
+    #[test]
+    fn suggestions_ordering_and_truncation() {
+        let candidates = ["tset", "best", "rest", "test", "toast", "unrelated"];
+        assert_eq!(
+            vec!["test", "best", "rest", "toast", "tset"],
+            suggestions("test", &candidates, 10, 2)
+        );
+        assert_eq!(vec!["test", "best"], suggestions("test", &candidates, 2, 2));
+        assert_eq!(
+            vec!["test", "best", "rest"],
+            suggestions("test", &candidates, 10, 1)
+        );
+        assert!(suggestions("test", &candidates, 0, 2).is_empty());
+    }
+
+    #[test]
+    fn hamming_bits_n_masks_padding() {
+        // 63-bit hashes whose padding bits differ
+        let a = [0xff, 0, 0, 0, 0, 0, 0, 0b0000_0000];
+        let b = [0xff, 0, 0, 0, 0, 0, 0, 0b0000_0001];
+        assert_eq!(Ok(0), hamming_bits_n(&a, &b, 63));
+        assert_eq!(Ok(1), hamming_bits_n(&a, &b, 64));
+
+        let c = [0x7f, 0, 0, 0, 0, 0, 0, 0b1111_1111];
+        assert_eq!(Ok(8), hamming_bits_n(&a, &c, 63));
+        assert_eq!(Ok(1), hamming_bits_n(&a, &c, 1));
+    }
+
+    #[test]
+    fn hamming_bits_n_zero_bits() {
+        assert_eq!(Ok(0), hamming_bits_n(&[0xff], &[0x00], 0));
+        assert_eq!(Ok(0), hamming_bits_n(&[], &[], 0));
+    }
+
+    #[test]
+    fn hamming_bits_n_errors() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 1, len_b: 2 }),
+            hamming_bits_n(&[0], &[0, 0], 8)
+        );
+        let err = hamming_bits_n(&[0], &[0], 9).unwrap_err();
+        assert_eq!(
+            StrSimError::OutOfRange {
+                requested: 9,
+                available: 8
+            },
+            err
+        );
+        assert_eq!(
+            "Requested 9 elements, but only 8 are available",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn levenshtein_char_weighted_rare_char() {
+        let mut weights = HashMap::new();
+        weights.insert('z', 3.0);
+        weights.insert('e', 0.5);
+
+        let rare = levenshtein_char_weighted("zoo", "oo", &weights);
+        let common = levenshtein_char_weighted("eoo", "oo", &weights);
+        assert_delta!(3.0, rare);
+        assert_delta!(0.5, common);
+        assert!(rare > common);
+    }
+
+    #[test]
+    fn levenshtein_char_weighted_default_weights() {
+        let weights = HashMap::new();
+        assert_delta!(
+            levenshtein("kitten", "sitting") as f64,
+            levenshtein_char_weighted("kitten", "sitting", &weights)
+        );
+        assert_delta!(0.0, levenshtein_char_weighted("", "", &weights));
+    }
+
+    #[test]
+    fn alignment_cost_chars() {
+        // substitutions between similar looking characters are cheap
+        let mut sub = HashMap::new();
+        for &(x, y) in &[('0', 'o'), ('o', '0'), ('1', 'l'), ('l', '1')] {
+            sub.insert((x, y), 0.25);
+        }
+        sub.insert(('a', 'b'), 1.0);
+
+        let a: Vec<char> = "h0la".chars().collect();
+        let b: Vec<char> = "holb".chars().collect();
+        assert_delta!(1.25, alignment_cost(&a, &b, 1.0, &sub));
+
+        let c: Vec<char> = "hola".chars().collect();
+        let d: Vec<char> = "hoi".chars().collect();
+        assert_delta!(3.0, alignment_cost(&c, &d, 1.0, &sub));
+        assert_delta!(1.5, alignment_cost(&c, &d, 0.5, &sub));
+    }
+
+    #[test]
+    fn alignment_cost_overrides_equal_elements() {
+        let mut sub = HashMap::new();
+        sub.insert(('x', 'x'), 0.5);
+        assert_delta!(0.5, alignment_cost(&['x'], &['x'], 1.0, &sub));
+        assert_delta!(0.0, alignment_cost(&['y'], &['y'], 1.0, &sub));
+        assert_delta!(0.0, alignment_cost::<char>(&[], &[], 1.0, &sub));
+        assert_delta!(2.0, alignment_cost(&['y', 'y'], &[], 1.0, &sub));
+    }
+
+    #[test]
+    fn alignment_cost_without_clone() {
+        #[derive(PartialEq, Eq, Hash)]
+        struct Word(String);
+
+        let word = |s: &str| Word(s.to_string());
+        let mut sub = HashMap::new();
+        sub.insert((word("colour"), word("color")), 0.1);
+
+        let a = [word("the"), word("colour"), word("red")];
+        let b = [word("the"), word("color"), word("red")];
+        assert_delta!(0.1, alignment_cost(&a, &b, 1.0, &sub));
+        assert_delta!(2.0, alignment_cost(&b, &a, 1.0, &sub));
+    }
+
+    #[test]
+    fn rank_indices_permutation() {
+        let candidates = ["kitten", "sitting", "knitting", "sitten", "mitten"];
+        // normalized levenshtein to "sitting": 4/7, 1.0, 6/8, 5/7 and 4/7
+        assert_eq!(
+            vec![1, 2, 3, 0, 4],
+            rank_indices("sitting", &candidates, normalized_levenshtein)
+        );
+    }
+
+    #[test]
+    fn rank_indices_stable_for_ties() {
+        let candidates = ["b", "a", "c"];
+        assert_eq!(vec![0, 1, 2], rank_indices("x", &candidates, jaro));
+        assert!(rank_indices("x", &[], jaro).is_empty());
+    }
+
+    #[test]
+    fn jaro_counts_chars_not_bytes() {
+        // 'é' is two bytes, but a single character
+        assert_delta!(jaro("abc", "abd"), jaro("ébc", "ébd"));
+        assert_delta!(
+            (1.0 / 2.0 + 1.0 / 3.0 + 1.0) / 3.0,
+            jaro("\u{e9}x", "\u{e9}yz")
+        );
+    }
+
+    #[test]
+    fn jaro_no_matchable_content() {
+        // a lone combining acute accent is non-empty in bytes and characters
+        let accent = "\u{301}";
+        assert_delta!(0.0, jaro(accent, ""));
+        assert_delta!(0.0, jaro("", accent));
+        assert_delta!(1.0, jaro(accent, accent));
+        assert_delta!(0.0, jaro(accent, "e"));
+        assert_delta!(0.0, jaro(" ", ""));
+        assert_delta!(0.0, jaro("   ", "abc"));
+        assert_delta!(1.0, jaro_winkler(" ", " "));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn levenshtein_fold_graphemes_case() {
+        assert_eq!(0, levenshtein_fold_graphemes("José", "JOSÉ"));
+        assert_eq!(0, levenshtein_fold_graphemes("", ""));
+        assert_eq!(3, levenshtein_fold_graphemes("", "Abc"));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn levenshtein_fold_graphemes_combining_marks() {
+        // precomposed and decomposed forms are the same grapheme
+        assert_eq!(0, levenshtein_fold_graphemes("Ame\u{301}lie", "AMÉLIE"));
+        // replacing a letter with combining marks is a single edit
+        assert_eq!(
+            1,
+            levenshtein_fold_graphemes("Amelie", "Ame\u{301}\u{323}lie")
+        );
+        assert_eq!(2, levenshtein("Amelie", "Ame\u{301}\u{323}lie"));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn levenshtein_fold_graphemes_accented() {
+        assert_eq!(2, levenshtein_fold_graphemes("Müller", "Mueller"));
+        assert_eq!(1, levenshtein_fold_graphemes("MÜLLER", "muller"));
+        assert_eq!(2, levenshtein_fold_graphemes("Ångström", "angstrom"));
+    }
+
+    #[test]
+    fn smart_similarity_multi_word() {
+        assert_delta!(
+            f64::from(token_set_ratio("the quick fox", "quick brown fox")) / 100.0,
+            smart_similarity("the quick fox", "quick brown fox")
+        );
+        assert_delta!(1.0, smart_similarity("fox", "Fox fox"));
+    }
+
+    #[test]
+    fn smart_similarity_short() {
+        assert_delta!(
+            jaro_winkler("dixon", "dicksonx"),
+            smart_similarity("dixon", "dicksonx")
+        );
+        assert_delta!(1.0, smart_similarity("", ""));
+    }
+
+    #[test]
+    fn smart_similarity_long() {
+        let a = "internationalization";
+        let b = "internationalisation";
+        assert_delta!(normalized_levenshtein(a, b), smart_similarity(a, b));
+        assert_delta!(
+            normalized_levenshtein("abc", "abcdefghijklm"),
+            smart_similarity("abc", "abcdefghijklm")
+        );
+    }
+
+    #[test]
+    fn interner_matches_string_based_distances() {
+        let texts = [
+            "the quick brown fox\njumps over\nthe lazy dog",
+            "the quick red fox\njumps over\nthe lazy dog",
+            "a quick brown fox\nleaps over\nthe lazy cat",
+            "",
+        ];
+
+        let mut interner = Interner::new();
+        for a in &texts {
+            for b in &texts {
+                assert_eq!(word_levenshtein(a, b), interner.word_levenshtein(a, b));
+                assert_eq!(line_levenshtein(a, b), interner.line_levenshtein(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn interner_ids_are_stable() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+        assert_eq!(2, interner.word_levenshtein("a b", "c"));
+        assert_eq!(3, interner.len());
+        assert_eq!(1, interner.intern("b"));
+        assert_eq!(3, interner.intern("d"));
+        assert_eq!(4, interner.len());
+    }
+
+    #[test]
+    fn word_levenshtein_whitespace() {
+        assert_eq!(0, word_levenshtein("a  b\tc", "a b c"));
+        assert_eq!(3, word_levenshtein("", "a b c"));
+    }
+
+    #[test]
+    fn levenshtein_percent_rounding() {
+        // 1 - 2 / 3
+        assert_delta!(33.3, levenshtein_percent("abc", "axy", 1), 1e-9);
+        assert_delta!(33.33, levenshtein_percent("abc", "axy", 2), 1e-9);
+        // 1 - 1 / 3
+        assert_delta!(66.7, levenshtein_percent("abc", "abx", 1), 1e-9);
+        assert_delta!(66.67, levenshtein_percent("abc", "abx", 2), 1e-9);
+        assert_delta!(67.0, levenshtein_percent("abc", "abx", 0), 1e-9);
+    }
+
+    #[test]
+    fn levenshtein_percent_edge_cases() {
+        assert_delta!(100.0, levenshtein_percent("", "", 2));
+        assert_delta!(0.0, levenshtein_percent("", "abc", 2));
+        assert_delta!(
+            normalized_levenshtein("abc", "abx") * 100.0,
+            levenshtein_percent("abc", "abx", 400)
+        );
+    }
+
+    #[test]
+    fn matching_blocks_difflib_parity() {
+        // expected values from difflib.SequenceMatcher(None, a, b).get_matching_blocks()
+        assert_eq!(
+            vec![(0, 0, 6), (6, 15, 23), (29, 38, 0)],
+            matching_blocks(
+                "private Thread currentThread;",
+                "private volatile Thread currentThread;"
+            )
+        );
+        assert_eq!(
+            vec![(1, 0, 2), (4, 3, 2), (6, 6, 0)],
+            matching_blocks("qabxcd", "abycdf")
+        );
+    }
+
+    #[test]
+    fn matching_blocks_edge_cases() {
+        assert_eq!(vec![(0, 0, 0)], matching_blocks("", ""));
+        assert_eq!(vec![(0, 3, 0)], matching_blocks("", "abc"));
+        assert_eq!(vec![(0, 0, 3), (3, 3, 0)], matching_blocks("aaa", "aaa"));
+        assert_eq!(vec![(0, 0, 1), (2, 2, 0)], matching_blocks("éa", "éb"));
+    }
+
+    #[test]
+    fn min_distance_transposition() {
+        let metrics: [fn(&str, &str) -> usize; 2] = [levenshtein, damerau_levenshtein];
+        assert_eq!(2, levenshtein("receive", "recieve"));
+        assert_eq!(1, min_distance("receive", "recieve", &metrics));
+        assert_eq!(3, min_distance("kitten", "sitting", &metrics));
     }
 
     #[test]
-    fn hamming_unequal_length() {
+    fn min_distance_no_metrics() {
+        assert_eq!(usize::MAX, min_distance("a", "b", &[]));
+    }
+
+    #[test]
+    fn ensemble_fit_recovers_weights() {
+        let words = [
+            ("martha", "marhta"),
+            ("dixon", "dicksonx"),
+            ("jellyfish", "smellyfish"),
+            ("kitten", "sitting"),
+            ("abc", "xyz"),
+            ("saturday", "sunday"),
+        ];
+        let pairs: Vec<(String, String, f64)> = words
+            .iter()
+            .map(|&(a, b)| {
+                let expected = 0.7 * jaro(a, b) + 0.3 * normalized_levenshtein(a, b);
+                (a.to_string(), b.to_string(), expected)
+            })
+            .collect();
+
+        let mut ensemble = Ensemble::new(vec![jaro, normalized_levenshtein]);
+        assert_delta!(0.5, ensemble.weights()[0]);
+        ensemble.fit(&pairs);
+        assert_delta!(0.7, ensemble.weights()[0], 1e-6);
+        assert_delta!(0.3, ensemble.weights()[1], 1e-6);
+        assert_delta!(
+            0.7 * jaro("night", "nacht") + 0.3 * normalized_levenshtein("night", "nacht"),
+            ensemble.score("night", "nacht"),
+            1e-6
+        );
+    }
+
+    #[test]
+    fn ensemble_fit_underdetermined() {
+        // both scorers are identical, so only their sum is determined
+        let mut ensemble = Ensemble::new(vec![jaro, jaro]);
+        ensemble.fit(&[("martha".to_string(), "marhta".to_string(), 0.5)]);
+        assert_delta!(0.5, ensemble.score("martha", "marhta"), 1e-9);
+
+        let mut ensemble = Ensemble::with_weights(&[(jaro, 2.0)]);
+        assert_delta!(2.0, ensemble.score("a", "a"));
+        ensemble.fit(&[]);
+        assert_eq!(&[0.0], ensemble.weights());
+    }
+
+    #[test]
+    fn jaro_winkler_abbrev_expands_words() {
+        let mut abbrevs = HashMap::new();
+        abbrevs.insert("St.", "Saint");
+        abbrevs.insert("Mt.", "Mount");
+        abbrevs.insert("Rd", "Road");
+
+        assert!(jaro_winkler("St. John", "Saint John") < 0.9);
+        assert_delta!(1.0, jaro_winkler_abbrev("St. John", "Saint John", &abbrevs));
+        assert_delta!(
+            1.0,
+            jaro_winkler_abbrev("Mt.  Hood Rd", "Mount Hood Road", &abbrevs)
+        );
+        assert_delta!(
+            jaro_winkler("Saint Johns", "Saint John"),
+            jaro_winkler_abbrev("St. Johns", "Saint John", &abbrevs)
+        );
+    }
+
+    #[test]
+    fn jaro_winkler_abbrev_whole_words_only() {
+        let mut abbrevs = HashMap::new();
+        abbrevs.insert("St", "Street");
+
+        // "Stone" contains "St" but is not replaced
+        assert_delta!(
+            jaro_winkler("Stone Street", "Stone Street"),
+            jaro_winkler_abbrev("Stone St", "Stone Street", &abbrevs)
+        );
+        assert_delta!(
+            jaro_winkler("Stone", "Street"),
+            jaro_winkler_abbrev("Stone", "St", &abbrevs)
+        );
+    }
+
+    #[test]
+    fn rle_levenshtein_repetitions() {
+        assert_eq!(0, rle_levenshtein("cooool", "cool"));
+        assert_eq!(0, rle_levenshtein("helloo", "hello"));
+        assert_eq!(0, rle_levenshtein("aaa", "a"));
+        assert_eq!(0, rle_levenshtein("", ""));
+    }
+
+    #[test]
+    fn rle_levenshtein_edits() {
+        assert_eq!(1, rle_levenshtein("hello", "hallo"));
+        assert_eq!(1, rle_levenshtein("aaa", ""));
+        // "abab" has no runs to collapse
+        assert_eq!(2, rle_levenshtein("abab", "ab"));
+    }
+
+    #[test]
+    fn local_similarity_embedded() {
+        assert_delta!(1.0, local_similarity("error in the matrix", "matrix"));
+        assert_delta!(1.0, local_similarity("matrix", "xx matrix yy"));
+        // one mismatch in the embedded word: 5 matches - 1 mismatch
+        assert_delta!(4.0 / 6.0, local_similarity("error in the matrix", "metrix"));
+    }
+
+    #[test]
+    fn local_similarity_edge_cases() {
+        assert_delta!(1.0, local_similarity("", ""));
+        assert_delta!(0.0, local_similarity("", "abc"));
+        assert_delta!(0.0, local_similarity("abc", ""));
+        assert_delta!(1.0, local_similarity("same", "same"));
+        assert_delta!(0.0, local_similarity("abc", "xyz"));
+    }
+
+    #[test]
+    fn distance_matrix_symmetric() {
+        let items = ["kitten", "sitting", "", "mitten"];
+        let matrix = distance_matrix(&items, levenshtein);
+        for (i, a) in items.iter().enumerate() {
+            for (j, b) in items.iter().enumerate() {
+                assert_eq!(levenshtein(a, b), matrix[i][j]);
+            }
+        }
+        assert!(distance_matrix(&[], levenshtein).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_distance_matrix_matches_sequential() {
+        let items = ["kitten", "sitting", "", "mitten", "smitten", "kitchen", "a"];
         assert_eq!(
-            Err(StrSimError::DifferentLengthArgs),
-            generic_hamming("ham".chars(), "hamming".chars())
+            distance_matrix(&items, levenshtein),
+            par_distance_matrix(&items, levenshtein)
         );
+        assert_eq!(
+            distance_matrix(&items, normalized_damerau_levenshtein),
+            par_distance_matrix(&items, normalized_damerau_levenshtein)
+        );
+        assert!(par_distance_matrix(&[], levenshtein).is_empty());
     }
 
     #[test]
-    fn hamming_names() {
-        assert_hamming_dist(14, "Friedrich Nietzs", "Jean-Paul Sartre")
+    fn lcs_recovers_subsequence() {
+        let a: Vec<char> = "ABCBDAB".chars().collect();
+        let b: Vec<char> = "BDCAB".chars().collect();
+        let subsequence = lcs(&a, &b);
+        assert_eq!(vec!['B', 'C', 'A', 'B'], subsequence);
+        assert_eq!(lcs_len(&a, &b), subsequence.len());
+        assert_eq!("BCAB", lcs_str("ABCBDAB", "BDCAB"));
     }
 
     #[test]
-    fn jaro_both_empty() {
-        assert_eq!(1.0, jaro("", ""));
+    fn lcs_edge_cases() {
+        assert_eq!("", lcs_str("", "abc"));
+        assert_eq!("", lcs_str("abc", "xyz"));
+        assert_eq!("über", lcs_str("über", "xüxbxexrx"));
+        assert!(lcs::<u8>(&[], &[]).is_empty());
     }
 
     #[test]
-    fn jaro_first_empty() {
-        assert_eq!(0.0, jaro("", "jaro"));
+    fn tie_break_policies() {
+        // every candidate other than the query itself gets the same score
+        let candidates = ["xbcd", "axcx", "abxxe", "xbxd", "abxx"];
+        let query = "abcd";
+        let metric: Scorer = |a, b| if a == b { 1.0 } else { 0.5 };
+
+        let names = |tie_break| -> Vec<&str> {
+            top_matches(query, &candidates, 5, metric, tie_break)
+                .iter()
+                .map(|m| m.0)
+                .collect()
+        };
+        assert_eq!(
+            vec!["xbcd", "axcx", "abxxe", "xbxd", "abxx"],
+            names(TieBreak::First)
+        );
+        assert_eq!(
+            vec!["abxx", "xbxd", "abxxe", "axcx", "xbcd"],
+            names(TieBreak::Last)
+        );
+        assert_eq!(
+            vec!["xbcd", "axcx", "xbxd", "abxx", "abxxe"],
+            names(TieBreak::Shortest)
+        );
+        assert_eq!(
+            vec!["abxx", "abxxe", "axcx", "xbcd", "xbxd"],
+            names(TieBreak::Alphabetical)
+        );
+
+        assert_eq!(
+            Some(("abxx", 0.5)),
+            best_match(query, &candidates, metric, TieBreak::Alphabetical)
+        );
+        assert_eq!(TieBreak::First, TieBreak::default());
     }
 
     #[test]
-    fn jaro_second_empty() {
-        assert_eq!(0.0, jaro("distance", ""));
+    fn top_matches_scores_before_ties() {
+        let candidates = ["zzz", "abd", "abc", "abe"];
+        assert_eq!(
+            vec![("abc", 1.0), ("abd", 1.0 - 1.0 / 3.0)],
+            top_matches(
+                "abc",
+                &candidates,
+                2,
+                normalized_levenshtein,
+                TieBreak::First
+            )
+        );
+        assert_eq!(
+            vec![("abc", 1.0), ("abe", 1.0 - 1.0 / 3.0)],
+            top_matches(
+                "abc",
+                &candidates,
+                2,
+                normalized_levenshtein,
+                TieBreak::Last
+            )
+        );
+        assert_eq!(
+            None,
+            best_match("abc", &[], normalized_levenshtein, TieBreak::First)
+        );
     }
 
     #[test]
-    fn jaro_same() {
-        assert_eq!(1.0, jaro("jaro", "jaro"));
+    fn ngram_size_zero_is_an_error() {
+        let idf = HashMap::new();
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            shared_ngrams("night", "night", 0)
+        );
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            jaccard("night", "night", 0)
+        );
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            jaccard_hashed("night", "night", 0)
+        );
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            cosine_ngram_idf("night", "night", 0, &idf)
+        );
+        assert_eq!(
+            "n-gram size must be at least 1",
+            StrSimError::InvalidNgramSize.to_string()
+        );
     }
 
     #[test]
-    fn jaro_multibyte() {
-        assert_delta!(0.818, jaro("testabctest", "testöঙ香test"), 0.001);
-        assert_delta!(0.818, jaro("testöঙ香test", "testabctest"), 0.001);
+    fn damerau_levenshtein_limit_within_limit() {
+        let pairs = [
+            ("ab", "bca"),
+            ("kitten", "sitting"),
+            ("a cat", "an abct"),
+            ("levenshtein", "löwenbräu"),
+            ("", "abc"),
+            ("abc", ""),
+            ("", ""),
+            ("specter", "spectre"),
+        ];
+        for &(a, b) in &pairs {
+            let distance = damerau_levenshtein(a, b);
+            assert_eq!(distance, damerau_levenshtein_limit(a, b, distance));
+            assert_eq!(distance, damerau_levenshtein_limit(a, b, distance + 3));
+        }
     }
 
     #[test]
-    fn jaro_diff_short() {
-        assert_delta!(0.767, jaro("dixon", "dicksonx"), 0.001);
+    fn damerau_levenshtein_limit_exceeded() {
+        assert_eq!(2, damerau_levenshtein_limit("kitten", "sitting", 1));
+        assert_eq!(1, damerau_levenshtein_limit("ab", "ba", 0));
+        assert_eq!(3, damerau_levenshtein_limit("abcdefgh", "xyzdefgh", 2));
+        // the length difference alone exceeds the limit
+        assert_eq!(3, damerau_levenshtein_limit("a", "abcdef", 2));
+        assert_eq!(3, damerau_levenshtein_limit("abc", "xyz", usize::MAX));
     }
 
     #[test]
-    fn jaro_diff_one_character() {
-        assert_eq!(0.0, jaro("a", "b"));
+    fn damerau_levenshtein_limit_matches_random() {
+        let mut seed = 7;
+        for len in 0..20 {
+            let a = random_string(&mut seed, len);
+            let b = random_string(&mut seed, len / 2 + 3);
+            let distance = damerau_levenshtein(&a, &b);
+            for limit in 0..distance + 2 {
+                assert_eq!(
+                    min(distance, limit + 1),
+                    damerau_levenshtein_limit(&a, &b, limit)
+                );
+            }
+        }
     }
 
     #[test]
-    fn jaro_same_one_character() {
-        assert_eq!(1.0, jaro("a", "a"));
+    fn weighted_levenshtein_default_costs() {
+        let costs = Costs::default();
+        for &(a, b) in &[("kitten", "sitting"), ("", "abc"), ("flaw", "lawn")] {
+            assert_delta!(levenshtein(a, b) as f64, weighted_levenshtein(a, b, &costs));
+        }
     }
 
     #[test]
-    fn generic_jaro_diff() {
-        assert_eq!(0.0, generic_jaro(&[1, 2], &[3, 4]));
+    fn normalized_weighted_levenshtein_non_unit_costs() {
+        let costs = Costs {
+            insert: 0.5,
+            delete: 2.0,
+            substitute: 1.5,
+        };
+        // "abc" -> "abcd" needs one insertion out of a maximum of 3 * 2.0 + 4 * 0.5
+        assert_delta!(
+            1.0 - 0.5 / 8.0,
+            normalized_weighted_levenshtein("abc", "abcd", &costs)
+        );
+        // "abcd" -> "abc" needs one deletion out of a maximum of 4 * 2.0 + 3 * 0.5
+        assert_delta!(
+            1.0 - 2.0 / 9.5,
+            normalized_weighted_levenshtein("abcd", "abc", &costs)
+        );
+        // no common characters: substituting is cheaper than deleting and inserting
+        assert_delta!(
+            1.0 - 3.0 / 5.0,
+            normalized_weighted_levenshtein("ab", "xy", &costs)
+        );
     }
 
     #[test]
-    fn jaro_diff_one_and_two() {
-        assert_delta!(0.83, jaro("a", "ab"), 0.01);
+    fn normalized_weighted_levenshtein_bounds() {
+        let costs = Costs {
+            insert: 1.0,
+            delete: 1.0,
+            substitute: 5.0,
+        };
+        assert_delta!(0.0, normalized_weighted_levenshtein("abc", "xyz", &costs));
+        assert_delta!(1.0, normalized_weighted_levenshtein("", "", &costs));
+        assert_delta!(0.0, normalized_weighted_levenshtein("", "abc", &costs));
+        assert_delta!(1.0, normalized_weighted_levenshtein("abc", "abc", &costs));
+
+        let free = Costs {
+            insert: 0.0,
+            delete: 0.0,
+            substitute: 0.0,
+        };
+        assert_delta!(1.0, normalized_weighted_levenshtein("abc", "xyz", &free));
     }
 
     #[test]
-    fn jaro_diff_two_and_one() {
-        assert_delta!(0.83, jaro("ab", "a"), 0.01);
+    fn phonetic_levenshtein_homophones() {
+        assert_eq!(3, levenshtein("Cristopher", "Kristofer"));
+        assert_eq!(1, phonetic_levenshtein("Cristopher", "Kristofer"));
+        assert_eq!(2, levenshtein("Stephen", "Steven"));
+        assert_eq!(1, phonetic_levenshtein("Stephen", "Steven"));
     }
 
     #[test]
-    fn jaro_diff_no_transposition() {
-        assert_delta!(0.822, jaro("dwayne", "duane"), 0.001);
+    fn phonetic_levenshtein_different_classes() {
+        // 'b' (1) and 'd' (3) are in different classes, vowels have none
+        assert_eq!(
+            levenshtein("bat", "dot"),
+            phonetic_levenshtein("bat", "dot")
+        );
+        assert_eq!(0, phonetic_levenshtein("", ""));
+        assert_eq!(3, phonetic_levenshtein("", "abc"));
     }
 
     #[test]
-    fn jaro_diff_with_transposition() {
-        assert_delta!(0.944, jaro("martha", "marhta"), 0.001);
-        assert_delta!(0.6, jaro("a jke", "jane a k"), 0.001);
+    fn adjacent_distances_progressive_edits() {
+        let versions = ["cat", "cart", "card", "cards", "cards"];
+        assert_eq!(vec![1, 1, 1, 0], adjacent_distances(&versions, levenshtein));
+        assert_eq!(vec![2], adjacent_distances(&["ab", "ba"], levenshtein));
+        assert_eq!(
+            vec![1],
+            adjacent_distances(&["ab", "ba"], damerau_levenshtein)
+        );
     }
 
     #[test]
-    fn jaro_names() {
+    fn adjacent_distances_too_few_items() {
+        assert!(adjacent_distances(&[], levenshtein).is_empty());
+        assert!(adjacent_distances(&["only"], levenshtein).is_empty());
+    }
+
+    #[test]
+    fn jaro_config_standard() {
+        let pairs = [
+            ("dixon", "dicksonx"),
+            ("martha", "marhta"),
+            ("cheeseburger", "cheese fries"),
+            ("", ""),
+            ("abc", ""),
+            ("Friedrich Nietzsche", "Jean-Paul Sartre"),
+        ];
+        let config = Jaro::new();
+        for &(a, b) in &pairs {
+            assert_delta!(jaro(a, b), config.similarity(a, b));
+            assert_delta!(jaro_winkler(a, b), config.winkler(a, b));
+        }
+    }
+
+    #[test]
+    fn jaro_config_prefix() {
+        // jaro("martha", "marhta") is 0.94444 with a common prefix of 3
+        let shorter_prefix = Jaro::new().max_prefix(2);
+        assert_delta!(0.95556, shorter_prefix.winkler("martha", "marhta"));
+        let larger_scale = Jaro::new().prefix_scale(0.2);
+        assert_delta!(0.97778, larger_scale.winkler("martha", "marhta"));
+        let no_boost = Jaro::new().boost_threshold(1.0);
         assert_delta!(
-            0.392,
-            jaro("Friedrich Nietzsche", "Jean-Paul Sartre"),
-            0.001
+            jaro("martha", "marhta"),
+            no_boost.winkler("martha", "marhta")
         );
     }
 
     #[test]
-    fn jaro_winkler_both_empty() {
-        assert_eq!(1.0, jaro_winkler("", ""));
+    fn jaro_config_long_string_adjustment() {
+        // "dwayne" and "duane": 4 matches, prefix 1, jaro-winkler 0.84
+        let adjusted = Jaro::new().long_string_adjustment(true);
+        assert_delta!(0.84, Jaro::new().winkler("dwayne", "duane"));
+        assert_delta!(
+            0.84 + 0.16 * 2.0 / 11.0,
+            adjusted.winkler("dwayne", "duane")
+        );
+        // too short for the adjustment
+        assert_delta!(
+            jaro_winkler("abcd", "abdc"),
+            adjusted.winkler("abcd", "abdc")
+        );
     }
 
     #[test]
-    fn jaro_winkler_first_empty() {
-        assert_eq!(0.0, jaro_winkler("", "jaro-winkler"));
+    fn fuzzy_set_similarity_near_duplicates() {
+        let a = ["machine-learning", "python", "data"];
+        let b = ["python3", "machine learning", "data"];
+        let exact = |x: &str, y: &str| if x == y { 1.0 } else { 0.0 };
+        assert_delta!(1.0 / 3.0, fuzzy_set_similarity(&a, &b, exact, 0.5));
+
+        let expected = (normalized_levenshtein("machine-learning", "machine learning")
+            + normalized_levenshtein("python", "python3")
+            + 1.0)
+            / 3.0;
+        assert_delta!(
+            expected,
+            fuzzy_set_similarity(&a, &b, normalized_levenshtein, 0.8)
+        );
     }
 
     #[test]
-    fn jaro_winkler_second_empty() {
-        assert_eq!(0.0, jaro_winkler("distance", ""));
+    fn fuzzy_set_similarity_matches_once() {
+        // both "colour" and "colors" are close to "color", which can only be
+        // matched once
+        let a = ["color"];
+        let b = ["colour", "colors"];
+        assert_delta!(
+            normalized_levenshtein("color", "colour") / 2.0,
+            fuzzy_set_similarity(&a, &b, normalized_levenshtein, 0.5)
+        );
+        assert_delta!(
+            0.0,
+            fuzzy_set_similarity(&a, &b, normalized_levenshtein, 0.9)
+        );
+        assert_delta!(
+            1.0,
+            fuzzy_set_similarity(&[], &[], normalized_levenshtein, 0.5)
+        );
+        assert_delta!(
+            0.0,
+            fuzzy_set_similarity(&a, &[], normalized_levenshtein, 0.5)
+        );
     }
 
     #[test]
-    fn jaro_winkler_same() {
-        assert_eq!(1.0, jaro_winkler("Jaro-Winkler", "Jaro-Winkler"));
+    fn sliding_hamming_matches_recomputed() {
+        let pattern = b"needle";
+        let stream = b"a haystack with a needle and a neddle in it";
+        let mut sliding = SlidingHamming::new(pattern, &stream[..pattern.len()]).unwrap();
+
+        for start in 1..=stream.len() - pattern.len() {
+            let end = start + pattern.len();
+            let distance = sliding.slide(stream[end - 1]);
+            assert_eq!(
+                generic_hamming(&pattern[..], &stream[start..end]).unwrap(),
+                distance
+            );
+            assert_eq!(distance, sliding.distance());
+        }
     }
 
     #[test]
-    fn jaro_winkler_multibyte() {
-        assert_delta!(0.89, jaro_winkler("testabctest", "testöঙ香test"), 0.001);
-        assert_delta!(0.89, jaro_winkler("testöঙ香test", "testabctest"), 0.001);
+    fn sliding_hamming_edge_cases() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 2, len_b: 3 }),
+            SlidingHamming::new(b"ab", b"abc").map(|s| s.distance())
+        );
+
+        let mut empty = SlidingHamming::new(b"", b"").unwrap();
+        assert_eq!(0, empty.distance());
+        assert_eq!(0, empty.slide(b'b'));
     }
 
     #[test]
-    fn jaro_winkler_diff_short() {
-        assert_delta!(0.813, jaro_winkler("dixon", "dicksonx"), 0.001);
-        assert_delta!(0.813, jaro_winkler("dicksonx", "dixon"), 0.001);
+    fn noise_sensitivity_deterministic() {
+        let first = noise_sensitivity("deterministic", 3, 50, jaro_winkler, 7);
+        let second = noise_sensitivity("deterministic", 3, 50, jaro_winkler, 7);
+        assert_eq!(first.to_bits(), second.to_bits());
+        assert!(first < 1.0);
+        assert_ne!(
+            first.to_bits(),
+            noise_sensitivity("deterministic", 3, 50, jaro_winkler, 8).to_bits()
+        );
     }
 
     #[test]
-    fn jaro_winkler_diff_one_character() {
-        assert_eq!(0.0, jaro_winkler("a", "b"));
+    fn noise_sensitivity_more_edits_degrade() {
+        let few = noise_sensitivity("a longer example string", 1, 200, normalized_levenshtein, 1);
+        let many = noise_sensitivity("a longer example string", 8, 200, normalized_levenshtein, 1);
+        assert!(few > many, "{} {}", few, many);
+        // at most one edit out of at least 22 characters
+        assert!(few >= 1.0 - 1.0 / 22.0);
     }
 
     #[test]
-    fn jaro_winkler_same_one_character() {
-        assert_eq!(1.0, jaro_winkler("a", "a"));
+    fn noise_sensitivity_edge_cases() {
+        assert_delta!(1.0, noise_sensitivity("abc", 0, 10, jaro, 3));
+        assert_delta!(1.0, noise_sensitivity("abc", 5, 0, jaro, 3));
+        // the only possible edit of the empty string is an insertion
+        assert_delta!(0.0, noise_sensitivity("", 1, 10, normalized_levenshtein, 3));
     }
 
     #[test]
-    fn jaro_winkler_diff_no_transposition() {
-        assert_delta!(0.84, jaro_winkler("dwayne", "duane"), 0.001);
+    fn classify_bands() {
+        // normalized levenshtein: 1.0, 0.75, 0.5 and 0.0
+        let metric: Scorer = normalized_levenshtein;
+        assert_eq!(
+            MatchClass::Match,
+            classify("abcd", "abcd", metric, 0.75, 0.5)
+        );
+        assert_eq!(
+            MatchClass::Match,
+            classify("abcd", "abcx", metric, 0.75, 0.5)
+        );
+        assert_eq!(
+            MatchClass::Uncertain,
+            classify("abcd", "abxx", metric, 0.75, 0.5)
+        );
+        assert_eq!(
+            MatchClass::NoMatch,
+            classify("abcd", "wxyz", metric, 0.75, 0.5)
+        );
     }
 
     #[test]
-    fn jaro_winkler_diff_with_transposition() {
-        assert_delta!(0.961, jaro_winkler("martha", "marhta"), 0.001);
-        assert_delta!(0.6, jaro_winkler("a jke", "jane a k"), 0.001);
+    fn classify_without_uncertain_band() {
+        let metric: Scorer = normalized_levenshtein;
+        assert_eq!(
+            MatchClass::Match,
+            classify("abcd", "abcx", metric, 0.75, 0.75)
+        );
+        assert_eq!(
+            MatchClass::NoMatch,
+            classify("abcd", "abxx", metric, 0.75, 0.75)
+        );
+        assert_eq!(
+            MatchClass::Match,
+            classify("abcd", "abcx", metric, 0.75, 0.9)
+        );
     }
 
     #[test]
-    fn jaro_winkler_names() {
+    fn name_similarity_reordered() {
+        let reordered = name_similarity("Smith, John", "John Smith");
+        assert!(reordered > jaro_winkler("smith john", "john smith"));
         assert_delta!(
-            0.452,
-            jaro_winkler("Friedrich Nietzsche", "Fran-Paul Sartre"),
-            0.001
+            (jaro_winkler("smith john", "john smith") + 1.0) / 2.0,
+            reordered
         );
+        assert_delta!(1.0, name_similarity("  JOHN   smith ", "john smith"));
     }
 
     #[test]
-    fn jaro_winkler_long_prefix() {
-        assert_delta!(0.866, jaro_winkler("cheeseburger", "cheese fries"), 0.001);
+    fn name_similarity_misspelled() {
+        let misspelled = name_similarity("Catherine Zeta-Jones", "Katherine Zeta Jones");
+        assert!(misspelled > 0.8, "{}", misspelled);
+        assert!(misspelled < 1.0);
+        assert!(name_similarity("John Smith", "Mary Jones") < 0.5);
     }
 
     #[test]
-    fn jaro_winkler_more_names() {
-        assert_delta!(0.868, jaro_winkler("Thorkel", "Thorgier"), 0.001);
+    fn argmax_metric_labeled() {
+        let metrics: [(&str, Scorer); 3] = [
+            ("levenshtein", normalized_levenshtein),
+            ("damerau", normalized_damerau_levenshtein),
+            ("jaro_winkler", jaro_winkler),
+        ];
+        // a transposition at the end: damerau 0.8, jaro-winkler 0.96
+        let (name, score) = argmax_metric("abcde", "abced", &metrics).unwrap();
+        assert_eq!("jaro_winkler", name);
+        assert_delta!(jaro_winkler("abcde", "abced"), score);
+
+        // no similarity at all for any metric, so the first one wins
+        assert_eq!(
+            Some(("levenshtein", 0.0)),
+            argmax_metric("abc", "xyz", &metrics)
+        );
     }
 
     #[test]
-    fn jaro_winkler_length_of_one() {
-        assert_delta!(0.738, jaro_winkler("Dinsdale", "D"), 0.001);
+    fn argmax_metric_no_metrics() {
+        assert_eq!(None, argmax_metric("a", "b", &[]));
     }
 
     #[test]
-    fn jaro_winkler_very_long_prefix() {
+    fn jaro_long_pair_precision() {
+        let a = "The quick brown fox jumps over the lazy dog while the cat sleeps on the warm windowsill";
+        let b =
+            "A quick brown dog jumped over the lazy fox as the cat slept by the cold window sill";
+        // 75 matches and 35 transpositions, exactly 83006 / 108315
+        assert_delta!(0.766_338_918_893_966_7, jaro(a, b), 1e-15);
+        assert_delta!(0.766_338_918_893_966_7, jaro(b, a), 1e-15);
+    }
+
+    #[test]
+    fn levenshtein_prefix_weighted_early_typo_costs_more() {
+        let early = levenshtein_prefix_weighted("identifier", "xdentifier", 0.8);
+        let late = levenshtein_prefix_weighted("identifier", "identifiex", 0.8);
+        assert_delta!(1.0, early);
+        assert_delta!(0.8_f64.powi(9), late);
+        assert!(early > late);
+    }
+
+    #[test]
+    fn levenshtein_prefix_weighted_no_decay() {
+        for &(a, b) in &[
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("abc", ""),
+            ("flaw", "lawn"),
+        ] {
+            assert_delta!(
+                levenshtein(a, b) as f64,
+                levenshtein_prefix_weighted(a, b, 1.0)
+            );
+        }
+        // inserting at the front costs 1.0 + 0.5 + 0.25
+        assert_delta!(1.75, levenshtein_prefix_weighted("", "abc", 0.5));
+    }
+
+    #[test]
+    fn report_fields() {
+        let report = report("night", "nacht");
+        assert_eq!(5, report.len_a);
+        assert_eq!(5, report.len_b);
+        assert_eq!(Some(2), report.scores.hamming);
+        assert_eq!(2, report.scores.levenshtein);
+        assert_eq!("nht", report.lcs);
+        assert_eq!(1, report.shared_bigrams);
+    }
+
+    #[test]
+    fn report_display() {
+        let text = report("kitten", "sitting").to_string();
+        assert!(!text.is_empty());
+        assert!(text.contains("levenshtein: 3 (0.571)"), "{}", text);
+        assert!(text.contains("hamming: -"), "{}", text);
+        assert!(!report("", "").to_string().is_empty());
+    }
+
+    #[test]
+    fn generic_osa_bytes() {
+        assert_eq!(1, generic_osa(b"abcd", b"abdc"));
+        assert_eq!(2, generic_levenshtein(&b"abcd"[..], &b"abdc"[..]));
+        // OSA can't edit the swapped bytes again
+        assert_eq!(3, generic_osa(b"ca", b"abc"));
+        assert_eq!(0, generic_osa::<u8>(b"", b""));
+        assert_eq!(3, generic_osa(b"", b"abc"));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn levenshtein_masking_timestamps() {
+        let timestamp = Regex::new(r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}").unwrap();
+        let a = "2024-01-07T10:15:30 INFO server started";
+        let b = "2025-11-23T08:01:02 INFO server started";
+        let c = "2025-11-23T08:01:02 WARN server started";
+
+        assert_eq!(0, levenshtein_masking(a, b, &timestamp, "<ts>"));
+        assert_eq!(4, levenshtein_masking(a, c, &timestamp, "<ts>"));
+        assert!(levenshtein(a, b) > 0);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn levenshtein_masking_literal_placeholder() {
+        let digits = Regex::new(r"(\d+)").unwrap();
+        // "$1" must not be expanded to the matched group
+        assert_eq!(0, levenshtein_masking("a1", "a22", &digits, "$1"));
+        assert_eq!(1, levenshtein_masking("a1", "b", &digits, ""));
+    }
+
+    #[test]
+    fn hamming_chars_slices() {
+        let a: Vec<char> = "größer 香港".chars().collect();
+        let b: Vec<char> = "grosser 香".chars().collect();
+        assert_eq!(hamming("größer 香港", "grosser 香"), hamming_chars(&a, &b));
+        assert_eq!(Ok(0), hamming_chars(&a, &a));
+        assert_eq!(Ok(0), hamming_chars(&[], &[]));
+        assert_eq!(Ok(2), hamming_chars(&['a', 'ö', 'c'], &['a', 'o', 'C']));
+    }
+
+    #[test]
+    fn hamming_chars_length_mismatch() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 1, len_b: 2 }),
+            hamming_chars(&['a'], &['a', 'b'])
+        );
+    }
+
+    #[test]
+    fn common_affixes_no_double_counting() {
+        assert_eq!((8, 4), common_affixes("prefix_match", "prefix_mismatch"));
+        assert_eq!((4, 0), common_affixes("abab", "ababab"));
+        assert_eq!((3, 0), common_affixes("aaa", "aaaa"));
+        assert_eq!((0, 4), common_affixes("xxxtest", "test"));
+    }
+
+    #[test]
+    fn common_affixes_identical_and_disjoint() {
+        assert_eq!((4, 0), common_affixes("same", "same"));
+        assert_eq!((0, 0), common_affixes("", ""));
+        assert_eq!((0, 0), common_affixes("abc", "xyz"));
+        assert_eq!((0, 3), common_affixes("xabc", "yabc"));
+        assert_eq!((1, 1), common_affixes("über", "üsr"));
+    }
+
+    #[test]
+    fn jaro_winkler_suffix_rewards_extension() {
+        let (a, b) = ("report_jan.csv", "report_feb.csv");
+        let sim = jaro(a, b);
+        let with_prefix = sim + 0.4 * (1.0 - sim);
+        assert_delta!(
+            with_prefix + 0.4 * (1.0 - with_prefix),
+            jaro_winkler_suffix(a, b, 0.1, 0.1)
+        );
+        assert!(jaro_winkler_suffix(a, b, 0.1, 0.1) > jaro_winkler(a, b));
+        assert_delta!(jaro_winkler(a, b), jaro_winkler_suffix(a, b, 0.1, 0.0));
+    }
+
+    #[test]
+    fn jaro_winkler_suffix_stays_in_range() {
+        assert_delta!(
+            1.0,
+            jaro_winkler_suffix("report.csv", "report.csv", 0.25, 0.25)
+        );
+        assert!(jaro_winkler_suffix("report_a.csv", "report_b.csv", 1.0, 1.0) <= 1.0);
         assert_delta!(
-            0.98519,
-            jaro_winkler("thequickbrownfoxjumpedoverx", "thequickbrownfoxjumpedovery")
+            jaro("abc", "xyz"),
+            jaro_winkler_suffix("abc", "xyz", 0.1, 0.1)
         );
     }
 
     #[test]
-    fn levenshtein_empty() {
-        assert_eq!(0, levenshtein("", ""));
+    fn canonicalize_typos() {
+        let items = ["color", "colour", "collor", "shape"];
+        let canonical = canonicalize(&items, 0.75, normalized_levenshtein);
+        assert_eq!(4, canonical.len());
+        assert_eq!("color", canonical["color"]);
+        assert_eq!("color", canonical["colour"]);
+        assert_eq!("color", canonical["collor"]);
+        assert_eq!("shape", canonical["shape"]);
     }
 
     #[test]
-    fn levenshtein_same() {
-        assert_eq!(0, levenshtein("levenshtein", "levenshtein"));
+    fn canonicalize_prefers_most_frequent() {
+        let items = ["collor", "colour", "colour", "color", "color"];
+        let canonical = canonicalize(&items, 0.75, normalized_levenshtein);
+        assert_eq!("colour", canonical["color"]);
+        assert_eq!("colour", canonical["collor"]);
+        assert!(canonicalize(&[], 0.75, normalized_levenshtein).is_empty());
     }
 
     #[test]
-    fn levenshtein_diff_short() {
-        assert_eq!(3, levenshtein("kitten", "sitting"));
+    fn deletion_index_matches_brute_force() {
+        let dictionary = [
+            "apple", "apply", "ample", "maple", "pale", "ale", "bale", "ball", "balls", "tall",
+            "a", "", "aa", "ab", "ba", "für", "fur",
+        ];
+        let index = DeletionIndex::new(&dictionary);
+        assert_eq!(dictionary.len(), index.len());
+
+        let queries = [
+            "apple", "appel", "aple", "applle", "bal", "pal", "", "b", "aaa", "fr", "fär", "zzz",
+        ];
+        for query in queries.iter() {
+            let expected: Vec<&str> = dictionary
+                .iter()
+                .copied()
+                .filter(|word| levenshtein(query, word) <= 1)
+                .collect();
+            assert_eq!(expected, index.lookup(query), "query {:?}", query);
+        }
     }
 
     #[test]
-    fn levenshtein_diff_with_space() {
-        assert_eq!(5, levenshtein("hello, world", "bye, world"));
+    fn deletion_index_skips_transpositions_and_duplicates() {
+        let index = DeletionIndex::new(&["abcd", "abcd", "acbd"]);
+        assert_eq!(2, index.len());
+        assert_eq!(vec!["abcd"], index.lookup("abcd"));
+        assert!(DeletionIndex::new(&[]).is_empty());
     }
 
     #[test]
-    fn levenshtein_diff_multibyte() {
-        assert_eq!(3, levenshtein("öঙ香", "abc"));
-        assert_eq!(3, levenshtein("abc", "öঙ香"));
+    fn numeric_aware_levenshtein_compares_values() {
+        assert_eq!(0, numeric_aware_levenshtein("file007", "file7"));
+        assert_eq!(0, numeric_aware_levenshtein("000", "0"));
+        assert_eq!(1, numeric_aware_levenshtein("v1.10", "v1.2"));
+        assert_eq!(1, numeric_aware_levenshtein("v1.10", "v1.1"));
+        assert_eq!(1, numeric_aware_levenshtein("12345", "54321"));
+        assert_eq!(0, numeric_aware_levenshtein("", ""));
     }
 
     #[test]
-    fn levenshtein_diff_longer() {
-        let a = "The quick brown fox jumped over the angry dog.";
-        let b = "Lorem ipsum dolor sit amet, dicta latine an eam.";
-        assert_eq!(37, levenshtein(a, b));
+    fn numeric_aware_levenshtein_other_characters() {
+        assert_eq!(3, numeric_aware_levenshtein("kitten", "sitting"));
+        assert_eq!(1, numeric_aware_levenshtein("file7", "fil7"));
+        assert_eq!(1, numeric_aware_levenshtein("a1b", "a1"));
+        assert_eq!(1, numeric_aware_levenshtein("ä10", "a010"));
     }
 
-    #[test]
-    fn levenshtein_first_empty() {
-        assert_eq!(7, levenshtein("", "sitting"));
+    /// Counts how often the wrapped metric is actually evaluated.
+    #[derive(Default)]
+    struct CountingMetric {
+        calls: std::cell::Cell<usize>,
     }
 
-    #[test]
-    fn levenshtein_second_empty() {
-        assert_eq!(6, levenshtein("kitten", ""));
+    impl Distance for CountingMetric {
+        fn raw(&self, a: &str, b: &str) -> f64 {
+            self.calls.set(self.calls.get() + 1);
+            levenshtein(a, b) as f64
+        }
+
+        fn normalized(&self, a: &str, b: &str) -> f64 {
+            self.calls.set(self.calls.get() + 1);
+            normalized_levenshtein(a, b)
+        }
     }
 
     #[test]
-    fn normalized_levenshtein_diff_short() {
-        assert_delta!(0.57142, normalized_levenshtein("kitten", "sitting"));
+    fn cached_hit_returns_stored_value() {
+        let cached = Cached::new(CountingMetric::default(), 2);
+        assert_eq!(3.0, cached.raw("kitten", "sitting"));
+        assert_eq!(3.0, cached.raw("kitten", "sitting"));
+        assert_eq!(1, cached.metric().calls.get());
+
+        assert_delta!(
+            normalized_levenshtein("kitten", "sitting"),
+            cached.normalized("kitten", "sitting")
+        );
+        assert_eq!(2, cached.metric().calls.get());
+        assert_eq!(1, cached.len());
+
+        cached.raw("sitting", "kitten");
+        assert_eq!(3, cached.metric().calls.get());
+        assert_eq!(2, cached.len());
     }
 
     #[test]
-    fn normalized_levenshtein_for_empty_strings() {
-        assert_delta!(1.0, normalized_levenshtein("", ""));
+    fn cached_evicts_least_recently_used() {
+        let cached = Cached::new(CountingMetric::default(), 2);
+        cached.raw("a", "b");
+        cached.raw("c", "d");
+        // touch ("a", "b") so ("c", "d") becomes the least recently used pair
+        cached.raw("a", "b");
+        cached.raw("e", "f");
+        assert_eq!(2, cached.len());
+        assert_eq!(3, cached.metric().calls.get());
+
+        cached.raw("a", "b");
+        assert_eq!(3, cached.metric().calls.get());
+        cached.raw("c", "d");
+        assert_eq!(4, cached.metric().calls.get());
+
+        cached.clear();
+        assert!(cached.is_empty());
     }
 
     #[test]
-    fn normalized_levenshtein_first_empty() {
-        assert_delta!(0.0, normalized_levenshtein("", "second"));
+    fn cached_evicts_pairs_sharing_a_string() {
+        let cached = Cached::new(CountingMetric::default(), 2);
+        cached.raw("a", "b");
+        cached.raw("a", "c");
+        cached.raw("a", "b");
+        cached.raw("b", "a");
+        assert_eq!(2, cached.len());
+        assert_eq!(3, cached.metric().calls.get());
+
+        cached.raw("a", "b");
+        assert_eq!(3, cached.metric().calls.get());
+        cached.raw("a", "c");
+        assert_eq!(4, cached.metric().calls.get());
+        assert_eq!(2, cached.len());
     }
 
     #[test]
-    fn normalized_levenshtein_second_empty() {
-        assert_delta!(0.0, normalized_levenshtein("first", ""));
+    fn cached_zero_capacity() {
+        let cached = Cached::new(CountingMetric::default(), 0);
+        cached.raw("a", "b");
+        cached.raw("a", "b");
+        assert_eq!(2, cached.metric().calls.get());
+        assert!(cached.is_empty());
     }
 
     #[test]
-    fn normalized_levenshtein_identical_strings() {
-        assert_delta!(1.0, normalized_levenshtein("identical", "identical"));
+    fn damerau_ratio_rewards_transpositions() {
+        assert!(f64::from(damerau_ratio("abcd", "abdc")) > levenshtein_percent("abcd", "abdc", 0));
+        assert_eq!(75, damerau_ratio("abcd", "abdc"));
+        assert_eq!(
+            levenshtein_percent("kitten", "sitting", 0),
+            f64::from(damerau_ratio("kitten", "sitting"))
+        );
     }
 
     #[test]
-    fn osa_distance_empty() {
-        assert_eq!(0, osa_distance("", ""));
+    fn ratio_bounds() {
+        assert_eq!(100, damerau_ratio("same", "same"));
+        assert_eq!(100, damerau_ratio("", ""));
+        assert_eq!(0, damerau_ratio("", "abc"));
+        assert_eq!(0, damerau_ratio("abc", "xyz"));
     }
 
     #[test]
-    fn osa_distance_same() {
-        assert_eq!(0, osa_distance("damerau", "damerau"));
+    fn hamming_full_distance_and_similarity() {
+        let (distance, similarity) = hamming_full("karolin", "kathrin").unwrap();
+        assert_eq!(3, distance);
+        assert_delta!(4.0 / 7.0, similarity);
+        assert_eq!(Ok((0, 1.0)), hamming_full("", ""));
+        assert_eq!(Ok((2, 0.0)), hamming_full("ab", "ba"));
     }
 
     #[test]
-    fn osa_distance_first_empty() {
-        assert_eq!(7, osa_distance("", "damerau"));
+    fn hamming_full_length_mismatch() {
+        assert_eq!(
+            Err(StrSimError::DifferentLengthArgs { len_a: 7, len_b: 4 }),
+            hamming_full("karolin", "kath")
+        );
     }
 
     #[test]
-    fn osa_distance_second_empty() {
-        assert_eq!(7, osa_distance("damerau", ""));
+    fn alignment_string_markers() {
+        let block = alignment_string("flaw", "lawn", '-');
+        let lines: Vec<&str> = block.lines().collect();
+        assert_eq!(vec!["flaw-", " ||| ", "-lawn"], lines);
     }
 
     #[test]
-    fn osa_distance_diff() {
-        assert_eq!(3, osa_distance("ca", "abc"));
+    fn align_gaps_and_empty_strings() {
+        assert_eq!(
+            ("abc".to_string(), "---".to_string()),
+            align("abc", "", '-')
+        );
+        assert_eq!(("".to_string(), "".to_string()), align("", "", '-'));
+        assert_eq!("\n\n", alignment_string("", "", '-'));
+        let (top, bottom) = align("straße", "strasse", '_');
+        assert_eq!(top.chars().count(), bottom.chars().count());
     }
 
     #[test]
-    fn osa_distance_diff_short() {
-        assert_eq!(3, osa_distance("damerau", "aderua"));
+    fn word_sets_with_stopwords() {
+        let options = WordSetOptions::new().lowercase(true).stopwords(&["the"]);
+        assert_delta!(1.0, word_jaccard("The Cat", "cat", &options));
+        assert_delta!(1.0, word_dice("The Cat", "cat", &options));
+
+        let exact = WordSetOptions::new();
+        assert_delta!(0.0, word_jaccard("The Cat", "cat", &exact));
+        assert_delta!(0.0, word_dice("The Cat", "cat", &exact));
     }
 
     #[test]
-    fn osa_distance_diff_reversed() {
-        assert_eq!(3, osa_distance("aderua", "damerau"));
+    fn word_sets_stopwords_follow_later_options() {
+        let options = WordSetOptions::new().stopwords(&["The"]).lowercase(true);
+        assert_delta!(1.0, word_jaccard("the cat", "Cat", &options));
+
+        let exact = options.lowercase(false);
+        assert_delta!(0.5, word_jaccard("the cat", "cat", &exact));
+        assert_delta!(1.0, word_jaccard("The cat", "cat", &exact));
     }
 
     #[test]
-    fn osa_distance_diff_multibyte() {
-        assert_eq!(3, osa_distance("öঙ香", "abc"));
-        assert_eq!(3, osa_distance("abc", "öঙ香"));
+    fn word_sets_partial_overlap() {
+        let options = WordSetOptions::new().lowercase(true);
+        assert_delta!(
+            1.0 / 3.0,
+            word_jaccard("red apple", "Green APPLE", &options)
+        );
+        assert_delta!(0.5, word_dice("red apple", "Green APPLE", &options));
+        assert_delta!(1.0, word_jaccard("", "...", &options));
+        assert_delta!(0.0, word_dice("", "word", &options));
     }
 
+    #[cfg(feature = "unicode")]
     #[test]
-    fn osa_distance_diff_unequal_length() {
-        assert_eq!(6, osa_distance("damerau", "aderuaxyz"));
+    fn word_sets_strip_accents() {
+        let options = WordSetOptions::new().lowercase(true).strip_accents(true);
+        assert_delta!(1.0, word_jaccard("Crème Brûlée", "creme brulee", &options));
+        assert_delta!(
+            0.0,
+            word_jaccard("Crème", "creme", &WordSetOptions::new().lowercase(true))
+        );
     }
 
     #[test]
-    fn osa_distance_diff_unequal_length_reversed() {
-        assert_eq!(6, osa_distance("aderuaxyz", "damerau"));
+    fn minhash_similarity_approximates_jaccard() {
+        let a = "The quick brown fox jumped over the angry dog. It was not amused by this.";
+        let b = "The quick brown fox jumps over the lazy dog. It was very much amused.";
+        for n in 1..5 {
+            assert_delta!(
+                jaccard(a, b, n).unwrap(),
+                minhash_similarity(a, b, n, 512).unwrap(),
+                0.1
+            );
+        }
+        assert_eq!(
+            minhash_similarity(a, b, 3, 64),
+            minhash_similarity(a, b, 3, 64)
+        );
     }
 
     #[test]
-    fn osa_distance_diff_comedians() {
-        assert_eq!(5, osa_distance("Stewart", "Colbert"));
+    fn minhash_similarity_edge_cases() {
+        assert_eq!(Ok(1.0), minhash_similarity("", "", 2, 16));
+        assert_eq!(Ok(1.0), minhash_similarity("abc", "abc", 2, 16));
+        assert_eq!(Ok(0.0), minhash_similarity("a", "b", 2, 16));
+        assert_eq!(Ok(0.0), minhash_similarity("abc", "xyz", 2, 16));
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            minhash_similarity("abc", "abd", 0, 16)
+        );
     }
 
     #[test]
-    fn osa_distance_many_transpositions() {
-        assert_eq!(4, osa_distance("abcdefghijkl", "bacedfgihjlk"));
+    fn minhash_similarity_requires_hashes() {
+        assert_eq!(
+            Err(StrSimError::InvalidHashCount),
+            minhash_similarity("abc", "abd", 2, 0)
+        );
+        assert_eq!(
+            Err(StrSimError::InvalidHashCount),
+            minhash_similarity("abc", "abc", 2, 0)
+        );
+        assert_eq!(
+            "number of hash functions must be at least 1",
+            StrSimError::InvalidHashCount.to_string()
+        );
     }
 
     #[test]
-    fn osa_distance_diff_longer() {
-        let a = "The quick brown fox jumped over the angry dog.";
-        let b = "Lehem ipsum dolor sit amet, dicta latine an eam.";
-        assert_eq!(36, osa_distance(a, b));
+    fn minhash_signatures_match_estimator() {
+        let docs = [
+            "The quick brown fox jumped over the angry dog.",
+            "The quick brown fox jumps over the lazy dog.",
+            "A completely different sentence about cats.",
+        ];
+        let signatures: Vec<MinHashSignature> = docs
+            .iter()
+            .map(|doc| MinHashSignature::new(doc, 3, 128).unwrap())
+            .collect();
+
+        for i in 0..docs.len() {
+            for j in i + 1..docs.len() {
+                assert_eq!(
+                    minhash_similarity(docs[i], docs[j], 3, 128).unwrap(),
+                    signatures[i].similarity(&signatures[j])
+                );
+            }
+        }
+        assert_delta!(1.0, signatures[0].similarity(&signatures[0]));
+        assert_eq!(
+            Err(StrSimError::InvalidNgramSize),
+            MinHashSignature::new("abc", 0, 128)
+        );
+        assert_eq!(
+            Err(StrSimError::InvalidHashCount),
+            MinHashSignature::new("abc", 2, 0)
+        );
     }
 
     #[test]
-    fn osa_distance_beginning_transposition() {
-        assert_eq!(1, osa_distance("foobar", "ofobar"));
+    #[should_panic(expected = "signatures must use the same n-gram size and number of hashes")]
+    fn minhash_signatures_must_be_compatible() {
+        let a = MinHashSignature::new("abcdef", 2, 64).unwrap();
+        let b = MinHashSignature::new("abcdef", 2, 32).unwrap();
+        a.similarity(&b);
     }
 
-    #[test]
-    fn osa_distance_end_transposition() {
-        assert_eq!(1, osa_distance("specter", "spectre"));
+    /// Applies an edit script to reconstruct both of the compared strings.
+    fn apply_edit_ops(ops: &[EditOp]) -> (String, String) {
+        let (mut a, mut b) = (String::new(), String::new());
+        for op in ops {
+            match op {
+                EditOp::Match(t) => {
+                    a.push_str(t);
+                    b.push_str(t);
+                }
+                EditOp::Substitute(x, y) => {
+                    a.push_str(x);
+                    b.push_str(y);
+                }
+                EditOp::Insert(t) => b.push_str(t),
+                EditOp::Delete(t) => a.push_str(t),
+            }
+        }
+        (a, b)
     }
 
     #[test]
-    fn osa_distance_restricted_edit() {
-        assert_eq!(4, osa_distance("a cat", "an abct"));
+    fn indel_operations_without_substitutions() {
+        let pairs = [
+            ("abc", "axc"),
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("abc", ""),
+            ("", ""),
+            ("Straße", "Strasse"),
+            ("abcdef", "fedcba"),
+        ];
+        for &(a, b) in pairs.iter() {
+            let ops = indel_operations(a, b);
+            assert!(!ops.iter().any(|op| matches!(op, EditOp::Substitute(_, _))));
+            let edits = ops
+                .iter()
+                .filter(|op| !matches!(op, EditOp::Match(_)))
+                .count();
+            assert_eq!(indel_distance(a, b), edits, "{} {}", a, b);
+            assert_eq!((a.to_string(), b.to_string()), apply_edit_ops(&ops));
+        }
     }
 
     #[test]
-    fn damerau_levenshtein_empty() {
-        assert_eq!(0, damerau_levenshtein("", ""));
+    fn indel_distance_counts_substitution_twice() {
+        assert_eq!(2, indel_distance("abc", "axc"));
+        assert_eq!(0, indel_distance("same", "same"));
+        assert_eq!(3, indel_distance("", "abc"));
+        assert_eq!(
+            vec![EditOp::Delete("b".into()), EditOp::Insert("x".into())],
+            indel_operations("abc", "axc")[1..3].to_vec()
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_same() {
-        assert_eq!(0, damerau_levenshtein("damerau", "damerau"));
+    fn lcs_similarity_chars() {
+        let a: Vec<char> = "kitten".chars().collect();
+        let b: Vec<char> = "sitting".chars().collect();
+        assert_delta!(8.0 / 13.0, lcs_similarity(&a, &b));
+        assert_delta!(8.0 / 13.0, lcs_str_similarity("kitten", "sitting"));
+        assert_delta!(0.0, lcs_similarity(&['a'], &['b']));
+        assert_delta!(0.0, lcs_similarity(&[], &['b']));
     }
 
     #[test]
-    fn damerau_levenshtein_first_empty() {
-        assert_eq!(7, damerau_levenshtein("", "damerau"));
+    fn lcs_similarity_tokens() {
+        let a = ["the", "quick", "brown", "fox"];
+        let b = ["the", "brown", "quick", "fox"];
+        assert_delta!(0.75, lcs_similarity(&a, &b));
+        assert_delta!(1.0, lcs_similarity(&a, &a));
+        assert_delta!(
+            indel_ratio("kitten", "sitting") / 100.0,
+            lcs_str_similarity("kitten", "sitting")
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_second_empty() {
-        assert_eq!(7, damerau_levenshtein("damerau", ""));
+    fn keyboard_levenshtein_adjacent_typos() {
+        let layout = Layout::default();
+        assert!(
+            keyboard_levenshtein("tge", "the", &layout)
+                < keyboard_levenshtein("tze", "the", &layout)
+        );
+        assert_delta!(0.5, keyboard_levenshtein("tge", "the", &layout));
+        assert_delta!(1.0, keyboard_levenshtein("tze", "the", &layout));
+        assert_delta!(0.0, keyboard_levenshtein("the", "the", &layout));
+        assert_delta!(1.0, keyboard_levenshtein("the", "th", &layout));
+        assert_delta!(1.0, keyboard_levenshtein("1", "2", &layout));
+
+        let cheap = Layout::qwerty().adjacent_cost(0.25);
+        assert_delta!(0.5, keyboard_levenshtein("hrllo", "jello", &cheap));
     }
 
     #[test]
-    fn damerau_levenshtein_diff() {
-        assert_eq!(2, damerau_levenshtein("ca", "abc"));
+    fn layout_adjacency() {
+        let layout = Layout::qwerty();
+        for &(a, b) in [
+            ('a', 'q'),
+            ('a', 'w'),
+            ('s', 'x'),
+            ('s', 'z'),
+            ('p', 'o'),
+            ('B', 'g'),
+        ]
+        .iter()
+        {
+            assert!(layout.are_adjacent(a, b), "{} {}", a, b);
+            assert!(layout.are_adjacent(b, a), "{} {}", b, a);
+        }
+        for &(a, b) in [
+            ('a', 'a'),
+            ('a', 'e'),
+            ('q', 'z'),
+            ('a', 'x'),
+            ('p', 'a'),
+            ('a', '!'),
+        ]
+        .iter()
+        {
+            assert!(!layout.are_adjacent(a, b), "{} {}", a, b);
+        }
     }
 
     #[test]
-    fn damerau_levenshtein_diff_short() {
-        assert_eq!(3, damerau_levenshtein("damerau", "aderua"));
+    fn percentile_of_known_scores() {
+        let scores = [0.2, 0.8, 0.4, 0.6, 1.0];
+        assert_delta!(0.6, percentile(&scores, 50.0).unwrap());
+        assert_delta!(0.2, percentile(&scores, 0.0).unwrap());
+        assert_delta!(1.0, percentile(&scores, 100.0).unwrap());
+        assert_delta!(0.4, percentile(&scores, 25.0).unwrap());
+        assert_delta!(0.9, percentile(&scores, 87.5).unwrap());
+        assert_delta!(0.7, percentile(&[0.7], 30.0).unwrap());
     }
 
     #[test]
-    fn damerau_levenshtein_diff_reversed() {
-        assert_eq!(3, damerau_levenshtein("aderua", "damerau"));
+    fn score_distribution_median() {
+        let scores = score_distribution(
+            "apple",
+            &["apple", "apply", "ample", "maple", "pear"],
+            normalized_levenshtein,
+        );
+        assert_eq!(5, scores.len());
+        assert!(scores.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_delta!(0.8, percentile(&scores, 50.0).unwrap());
+        assert!(score_distribution("apple", &[], normalized_levenshtein).is_empty());
     }
 
     #[test]
-    fn damerau_levenshtein_diff_multibyte() {
-        assert_eq!(3, damerau_levenshtein("öঙ香", "abc"));
-        assert_eq!(3, damerau_levenshtein("abc", "öঙ香"));
+    fn percentile_invalid_input() {
+        assert_eq!(None, percentile(&[], 50.0));
+        assert_eq!(None, percentile(&[0.5], -1.0));
+        assert_eq!(None, percentile(&[0.5], 100.5));
+        assert_eq!(None, percentile(&[0.5], f64::NAN));
     }
 
     #[test]
-    fn damerau_levenshtein_diff_unequal_length() {
-        assert_eq!(6, damerau_levenshtein("damerau", "aderuaxyz"));
+    fn jaro_exact_avoids_rounding_error() {
+        // 3 matches, no transpositions: (3/8 + 3/5 + 1) / 3 = 0.55 exactly
+        assert_eq!(0.55, jaro_exact("dicksonx", "duane"));
+        assert_ne!(0.55, jaro("dicksonx", "duane"));
+        assert_delta!(0.55, jaro("dicksonx", "duane"));
+
+        assert_eq!(17.0 / 18.0, jaro_exact("martha", "marhta"));
+        assert_ne!(17.0 / 18.0, jaro("martha", "marhta"));
     }
 
     #[test]
-    fn damerau_levenshtein_diff_unequal_length_reversed() {
-        assert_eq!(6, damerau_levenshtein("aderuaxyz", "damerau"));
+    fn jaro_exact_agrees_with_jaro() {
+        let pairs = [
+            ("", ""),
+            ("", "abc"),
+            ("abc", "xyz"),
+            ("same", "same"),
+            ("Friedrich Nietzsche", "Jean-Paul Sartre"),
+            ("Thorkel", "Thorgier"),
+            ("Dinsdale", "D"),
+            ("Überschriftenvergabe", "Überschriftenvergebung"),
+        ];
+        for &(a, b) in pairs.iter() {
+            assert_delta!(jaro(a, b), jaro_exact(a, b), 1e-12);
+        }
+        assert_eq!(1.0, jaro_exact("", ""));
+        assert_eq!(0.0, jaro_exact("abc", "xyz"));
     }
 
     #[test]
-    fn damerau_levenshtein_diff_comedians() {
-        assert_eq!(5, damerau_levenshtein("Stewart", "Colbert"));
+    fn grouped_best_match_picks_member_across_groups() {
+        let groups = [
+            ("toyota", vec!["corolla", "camry", "prius"]),
+            ("honda", vec!["civic", "accord", "jazz"]),
+        ];
+        assert_eq!(
+            Some(("honda", "civic", 0.8)),
+            grouped_best_match("civc", &groups, normalized_levenshtein)
+        );
+        assert_eq!(
+            Some(("toyota", "camry")),
+            grouped_best_match("camri", &groups, normalized_levenshtein).map(|(g, m, _)| (g, m))
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_many_transpositions() {
-        assert_eq!(4, damerau_levenshtein("abcdefghijkl", "bacedfgihjlk"));
+    fn grouped_best_match_ties_and_empty_groups() {
+        let groups = [("empty", vec![]), ("a", vec!["ab"]), ("b", vec!["ab"])];
+        assert_eq!(
+            Some(("a", "ab", 1.0)),
+            grouped_best_match("ab", &groups, normalized_levenshtein)
+        );
+        let empty: [(&str, Vec<&str>); 1] = [("empty", vec![])];
+        assert_eq!(
+            None,
+            grouped_best_match("ab", &empty, normalized_levenshtein)
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_diff_longer() {
-        let a = "The quick brown fox jumped over the angry dog.";
-        let b = "Lehem ipsum dolor sit amet, dicta latine an eam.";
-        assert_eq!(36, damerau_levenshtein(a, b));
+    fn levenshtein_max_len_trips() {
+        let limited = Levenshtein::new().max_len(5);
+        assert_eq!(Ok(1), limited.try_distance("hello", "hallo"));
+        assert_eq!(
+            Err(StrSimError::InputTooLong { len: 6, max_len: 5 }),
+            limited.try_distance("hello", "hellos")
+        );
+        assert_eq!(
+            Err(StrSimError::InputTooLong { len: 7, max_len: 5 }),
+            limited.try_distance("größere", "a")
+        );
+        assert_eq!(Ok(3), Levenshtein::new().try_distance("kitten", "sitting"));
+        assert_eq!(
+            Err(StrSimError::InputTooLong { len: 1, max_len: 0 }),
+            Levenshtein::new().max_len(0).try_distance("", "a")
+        );
+        assert_eq!(
+            Err(StrSimError::InputTooLong {
+                len: 12,
+                max_len: 5
+            }),
+            limited.try_distance("hello", "hello, world")
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_beginning_transposition() {
-        assert_eq!(1, damerau_levenshtein("foobar", "ofobar"));
+    fn levenshtein_max_len_as_distance() {
+        let limited = Levenshtein::new().max_len(5);
+        assert_eq!(f64::INFINITY, limited.raw("hello", "hellos"));
+        assert_eq!(0.0, limited.normalized("hello", "hellos"));
+        assert_eq!(1.0, limited.raw("hello", "hallo"));
+        assert_delta!(0.8, limited.normalized("hello", "hallo"));
     }
 
     #[test]
-    fn damerau_levenshtein_end_transposition() {
-        assert_eq!(1, damerau_levenshtein("specter", "spectre"));
+    fn input_too_long_display() {
+        assert_eq!(
+            "Input of 6 characters exceeds the maximum of 5",
+            StrSimError::InputTooLong { len: 6, max_len: 5 }.to_string()
+        );
     }
 
     #[test]
-    fn damerau_levenshtein_unrestricted_edit() {
-        assert_eq!(3, damerau_levenshtein("a cat", "an abct"));
+    fn exact_decay_similarity_identical() {
+        assert_eq!(1.0, exact_decay_similarity("", "", 1.0));
+        assert_eq!(1.0, exact_decay_similarity("identical", "identical", 0.5));
+        assert_eq!(1.0, exact_decay_similarity("ünïcödé", "ünïcödé", 10.0));
     }
 
     #[test]
-    fn normalized_damerau_levenshtein_diff_short() {
+    fn exact_decay_similarity_decays_per_edit() {
+        let long_a = "a".repeat(1000);
+        let long_b = format!("{}b", "a".repeat(999));
         assert_delta!(
-            0.27272,
-            normalized_damerau_levenshtein("levenshtein", "löwenbräu")
+            (-1.0_f64).exp(),
+            exact_decay_similarity(&long_a, &long_b, 1.0)
+        );
+        assert_delta!((-0.5_f64).exp(), exact_decay_similarity("cat", "cut", 0.5));
+        assert_delta!(
+            (-1.5_f64).exp(),
+            exact_decay_similarity("kitten", "sitting", 0.5)
         );
+        assert!(exact_decay_similarity("cat", "cut", 0.5) < 1.0);
     }
 
     #[test]
-    fn normalized_damerau_levenshtein_for_empty_strings() {
-        assert_delta!(1.0, normalized_damerau_levenshtein("", ""));
+    fn required_insertions_multiset_difference() {
+        let expected: HashMap<char, usize> = [('a', 1), ('b', 1)].iter().copied().collect();
+        assert_eq!(expected, required_insertions("abc", "aabbc"));
+
+        let expected: HashMap<char, usize> = [('ß', 2), ('x', 1)].iter().copied().collect();
+        assert_eq!(expected, required_insertions("yyy", "xßß"));
     }
 
     #[test]
-    fn normalized_damerau_levenshtein_first_empty() {
-        assert_delta!(0.0, normalized_damerau_levenshtein("", "flower"));
+    fn required_insertions_nothing_missing() {
+        assert!(required_insertions("aabbc", "abc").is_empty());
+        assert!(required_insertions("listen", "silent").is_empty());
+        assert!(required_insertions("", "").is_empty());
+        assert_eq!(
+            anagram_distance("abc", "aabbcdd"),
+            required_insertions("abc", "aabbcdd")
+                .values()
+                .sum::<usize>()
+        );
     }
 
     #[test]
-    fn normalized_damerau_levenshtein_second_empty() {
-        assert_delta!(0.0, normalized_damerau_levenshtein("tree", ""));
+    fn closest_pair_finds_near_duplicates() {
+        let items = ["receive", "believe", "recieve", "achieve", "perceive"];
+        assert_eq!(Some((0, 2, 2)), closest_pair(&items, levenshtein));
+        assert_eq!(Some((0, 2, 1)), closest_pair(&items, osa_distance));
     }
 
     #[test]
-    fn normalized_damerau_levenshtein_identical_strings() {
-        assert_delta!(
-            1.0,
-            normalized_damerau_levenshtein("sunglasses", "sunglasses")
-        );
+    fn closest_pair_matches_brute_force() {
+        let items = [
+            "a", "abcdefgh", "xyz", "abcdefg", "", "hello", "yellow", "fellow", "b", "abcdeff",
+        ];
+        let mut expected = None;
+        for i in 0..items.len() {
+            for j in i + 1..items.len() {
+                let distance = levenshtein(items[i], items[j]);
+                if expected.is_none_or(|(_, _, best)| distance < best) {
+                    expected = Some((i, j, distance));
+                }
+            }
+        }
+        assert_eq!(expected, closest_pair(&items, levenshtein));
+        assert_eq!(None, closest_pair(&["single"], levenshtein));
+        assert_eq!(None, closest_pair(&[], levenshtein));
     }
 
-    #[test]
-    fn sorensen_dice_all() {
-        // test cases taken from
-        // https://github.com/aceakash/string-similarity/blob/f83ba3cd7bae874c20c429774e911ae8cff8bced/src/spec/index.spec.js#L11
+    /// Makes substituting close integers cheap and inserting zeros free.
+    struct IntegerCosts;
 
-        assert_delta!(1.0, sorensen_dice("a", "a"));
-        assert_delta!(0.0, sorensen_dice("a", "b"));
-        assert_delta!(1.0, sorensen_dice("", ""));
-        assert_delta!(0.0, sorensen_dice("a", ""));
-        assert_delta!(0.0, sorensen_dice("", "a"));
-        assert_delta!(1.0, sorensen_dice("apple event", "apple    event"));
-        assert_delta!(0.90909, sorensen_dice("iphone", "iphone x"));
-        assert_delta!(0.0, sorensen_dice("french", "quebec"));
-        assert_delta!(1.0, sorensen_dice("france", "france"));
-        assert_delta!(0.2, sorensen_dice("fRaNce", "france"));
-        assert_delta!(0.8, sorensen_dice("healed", "sealed"));
+    impl EditCosts<i64> for IntegerCosts {
+        fn insert(&self, elem: &i64) -> f64 {
+            if *elem == 0 {
+                0.0
+            } else {
+                5.0
+            }
+        }
+
+        fn delete(&self, _: &i64) -> f64 {
+            5.0
+        }
+
+        fn substitute(&self, from: &i64, to: &i64) -> f64 {
+            (from - to).abs() as f64 / 10.0
+        }
+    }
+
+    #[test]
+    fn generic_weighted_levenshtein_custom_costs() {
         assert_delta!(
-            0.78788,
-            sorensen_dice("web applications", "applications of the web")
+            0.0,
+            generic_weighted_levenshtein(&[1, 2, 3], &[1, 2, 3], &IntegerCosts)
         );
         assert_delta!(
-            0.92,
-            sorensen_dice(
-                "this will have a typo somewhere",
-                "this will huve a typo somewhere"
-            )
+            0.3,
+            generic_weighted_levenshtein(&[1, 2, 3], &[1, 5, 3], &IntegerCosts)
         );
         assert_delta!(
-            0.60606,
-            sorensen_dice(
-                "Olive-green table for sale, in extremely good condition.",
-                "For sale: table in very good  condition, olive green in colour."
-            )
+            0.0,
+            generic_weighted_levenshtein(&[1, 2], &[0, 1, 0, 2, 0], &IntegerCosts)
         );
         assert_delta!(
-            0.25581,
-            sorensen_dice(
-                "Olive-green table for sale, in extremely good condition.",
-                "For sale: green Subaru Impreza, 210,000 miles"
-            )
+            10.0,
+            generic_weighted_levenshtein(&[1, 2], &[], &IntegerCosts)
         );
+        // substituting 1 with 200 costs more than deleting and inserting
         assert_delta!(
-            0.14118,
-            sorensen_dice(
-                "Olive-green table for sale, in extremely good condition.",
-                "Wanted: mountain bike with at least 21 gears."
-            )
+            10.0,
+            generic_weighted_levenshtein(&[1], &[200], &IntegerCosts)
         );
         assert_delta!(
-            0.77419,
-            sorensen_dice("this has one extra word", "this has one word")
+            9.9,
+            generic_weighted_levenshtein(&[1], &[100], &IntegerCosts)
         );
     }
+
+    #[test]
+    fn generic_weighted_levenshtein_with_costs_matches_strings() {
+        let costs = Costs {
+            insert: 2.0,
+            delete: 0.5,
+            substitute: 3.0,
+        };
+        for &(a, b) in [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("flaw", "lawn"),
+            ("ß", "ss"),
+        ]
+        .iter()
+        {
+            let a_chars: Vec<char> = a.chars().collect();
+            let b_chars: Vec<char> = b.chars().collect();
+            assert_delta!(
+                weighted_levenshtein(a, b, &costs),
+                generic_weighted_levenshtein(&a_chars, &b_chars, &costs)
+            );
+        }
+    }
 }
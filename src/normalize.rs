@@ -0,0 +1,115 @@
+//! Preprocessing functions to normalize strings before comparing them.
+
+#[cfg(feature = "caseless")]
+use caseless::default_case_fold_str;
+#[cfg(feature = "unicode")]
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Replaces every run of whitespace with a single space and removes leading
+/// and trailing whitespace.
+///
+/// ```
+/// use strsim::normalize::collapse_whitespace;
+///
+/// assert_eq!("hello world", collapse_whitespace("  hello \t\n world "));
+/// ```
+pub fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Removes all ASCII punctuation characters.
+///
+/// ```
+/// use strsim::normalize::strip_punctuation;
+///
+/// assert_eq!("Hello world", strip_punctuation("Hello, world!"));
+/// ```
+pub fn strip_punctuation(s: &str) -> String {
+    s.chars().filter(|c| !c.is_ascii_punctuation()).collect()
+}
+
+/// Converts the string to lowercase, so strings only differing in case
+/// compare equal.
+///
+/// ```
+/// use strsim::normalize::fold_case;
+///
+/// assert_eq!("straße", fold_case("STRAẞE"));
+/// ```
+pub fn fold_case(s: &str) -> String {
+    s.to_lowercase()
+}
+
+/// Applies Unicode default case folding, so strings only differing in case
+/// compare equal as defined by the Unicode standard. Unlike [`fold_case`],
+/// this also handles characters whose case folding differs from their
+/// lowercase form, e.g. `'ß'` folds to `"ss"`.
+///
+/// ```
+/// use strsim::normalize::fold;
+///
+/// assert_eq!(fold("STRASSE"), fold("straße"));
+/// ```
+#[cfg(feature = "caseless")]
+pub fn fold(s: &str) -> String {
+    default_case_fold_str(s)
+}
+
+/// Removes diacritics by decomposing the string (NFD) and dropping all
+/// combining marks.
+///
+/// ```
+/// use strsim::normalize::strip_diacritics;
+///
+/// assert_eq!("naive", strip_diacritics("naïve"));
+/// ```
+#[cfg(feature = "unicode")]
+pub fn strip_diacritics(s: &str) -> String {
+    s.nfd().filter(|&c| !is_combining_mark(c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapse_whitespace_runs() {
+        assert_eq!("a b c", collapse_whitespace("a  b\t\tc"));
+        assert_eq!("a b", collapse_whitespace("\n a\u{a0}b \n"));
+        assert_eq!("", collapse_whitespace("   "));
+    }
+
+    #[test]
+    fn strip_punctuation_ascii() {
+        assert_eq!("St Johns", strip_punctuation("St. John's"));
+        assert_eq!("", strip_punctuation("!?.,;:"));
+        assert_eq!("naïve", strip_punctuation("naïve"));
+    }
+
+    #[test]
+    fn fold_case_mixed() {
+        assert_eq!("hello world", fold_case("HeLLo WoRLD"));
+        assert_eq!("österreich", fold_case("ÖSTERREICH"));
+    }
+
+    #[cfg(feature = "caseless")]
+    #[test]
+    fn fold_differs_from_lowercase() {
+        assert_eq!("ß", fold_case("ß"));
+        assert_eq!("ss", fold("ß"));
+        // final and non-final sigma fold to the same character
+        assert_eq!("σ", fold("ς"));
+        assert_eq!("ς", fold_case("ς"));
+        assert_eq!("hello world", fold("HeLLo WoRLD"));
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn strip_diacritics_decomposes() {
+        assert_eq!("naive", strip_diacritics("naïve"));
+        assert_eq!("Creme brulee", strip_diacritics("Crème brûlée"));
+        // already decomposed input
+        assert_eq!("cafe", strip_diacritics("cafe\u{301}"));
+        assert_eq!("香港", strip_diacritics("香港"));
+    }
+}